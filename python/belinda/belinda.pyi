@@ -1 +1,174 @@
-import typing
\ No newline at end of file
+import typing
+from typing import Any, Callable, Dict, List, Optional, Sequence, Tuple, Union
+
+import polars as pl
+
+# `None`/`False` hide progress, `True` draws a terminal bar; tqdm-like objects (with an
+# `update` method) and `callback(done, total)` callables are updated as work proceeds.
+Progress = Union[None, bool, Any, Callable[[int, Optional[int]], None]]
+
+class SingletonMode:
+    AutoPopulate: SingletonMode
+    Ignore: SingletonMode
+    AsIs: SingletonMode
+
+class CompressedWriter:
+    """A binary file compressed according to its extension, see `open_compressed`."""
+    closed: bool
+    def write(self, data: bytes) -> int: ...
+    def flush(self) -> None: ...
+    def close(self) -> None: ...
+    def writable(self) -> bool: ...
+    def __enter__(self) -> CompressedWriter: ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+
+class Graph:
+    def __init__(
+        self,
+        filepath: str,
+        progress: Progress = None,
+        sep: Optional[str] = None,
+        comment: Optional[str] = "#",
+        skip_header: bool = False,
+        columns: Tuple[int, int] = (0, 1),
+        self_loops: str = "drop",
+        duplicates: str = "drop",
+        symmetrize: str = "union",
+        directed: bool = False,
+    ) -> None: ...
+    @property
+    def n(self) -> int: ...
+    @property
+    def m(self) -> int: ...
+    @property
+    def read_report(self) -> Optional[Dict[str, int]]: ...
+    @property
+    def directed(self) -> bool: ...
+    @property
+    def m_arcs(self) -> int: ...
+    def subgraph(self, nodes: Union[bytes, pl.Series]) -> Graph: ...
+    def write_edgelist(self, filepath: str) -> None: ...
+    def nodes(
+        self,
+        clus: Optional[pl.DataFrame] = None,
+        verbose: bool = False,
+        pagerank: bool = False,
+        damping: float = 0.85,
+        eigenvector: bool = False,
+        k_hop: Optional[int] = None,
+        tolerance: float = 1e-6,
+        max_iterations: int = 100,
+        betweenness_samples: Optional[int] = None,
+        epsilon: Optional[float] = None,
+        delta: float = 0.1,
+        seed: int = 0,
+    ) -> pl.DataFrame: ...
+    def edges(self, clus: Optional[pl.DataFrame] = None) -> pl.DataFrame: ...
+    def covered_edges(self, n: pl.Series, progress: Progress = None) -> pl.Series: ...
+    def edge_ids(self, src: pl.Series, dst: pl.Series, as_set: bool = False) -> pl.Series: ...
+    def covered_edges_count(self, n: pl.Series, progress: Progress = None) -> int: ...
+    def num_strong_components(self) -> int: ...
+    def largest_strong_component(self) -> int: ...
+    def num_weak_components(self) -> int: ...
+    def bfs(self, source: int, max_depth: Optional[int] = None) -> pl.DataFrame: ...
+    def shortest_path(self, a: int, b: int) -> Optional[List[int]]: ...
+    def ego(self, nodes: Union[int, Sequence[int]], radius: int = 1) -> bytes: ...
+    def components(self) -> pl.DataFrame: ...
+    def num_biconnected_components(self) -> int: ...
+    def articulation_points(self) -> pl.Series: ...
+    def bridges(self) -> pl.DataFrame: ...
+    def biconnected_components(self) -> pl.DataFrame: ...
+    def num_components(self) -> int: ...
+    def largest_component(self) -> int: ...
+
+def set_nthreads(nthreads: Optional[int] = None) -> None: ...
+def get_nthreads() -> int: ...
+def push_nthreads(nthreads: int) -> None: ...
+def pop_nthreads() -> None: ...
+def set_log_level(level: int) -> None: ...
+def read_membership(
+    g: Graph,
+    filepath: str,
+    sep: str = "\t",
+    mode: SingletonMode = SingletonMode.AsIs,
+    force_string_labels: bool = False,
+    progress: Progress = None,
+) -> pl.DataFrame: ...
+def read_membership_series(
+    g: Graph,
+    nodes: pl.Series,
+    cids: pl.Series,
+    mode: SingletonMode = SingletonMode.AsIs,
+    progress: Progress = None,
+) -> pl.DataFrame: ...
+def read_json(
+    g: Graph, filepath: str, mode: SingletonMode = SingletonMode.AsIs, progress: Progress = None
+) -> pl.DataFrame: ...
+def open_compressed(filepath: str) -> CompressedWriter: ...
+def cluster_leiden(
+    g: Graph,
+    objective: str = "cpm",
+    resolution: float = 1.0,
+    seed: int = 0,
+    iterations: int = 2,
+    initial: Optional[pl.DataFrame] = None,
+) -> pl.DataFrame: ...
+def cluster_label_propagation(g: Graph, seed: int = 0, max_iterations: int = 100) -> pl.DataFrame: ...
+def quotient_edges(g: Graph, clus: pl.DataFrame) -> pl.DataFrame: ...
+def quotient_graph(g: Graph, clus: pl.DataFrame) -> Tuple[Graph, pl.DataFrame]: ...
+def popcnt(series: pl.Series) -> pl.Series: ...
+def union(series: pl.Series) -> pl.Series: ...
+def cc_labels(g: Graph, series: pl.Series) -> pl.Series: ...
+def cc_size(g: Graph, series: pl.Series) -> pl.Series: ...
+def nodeset_to_list(g: Graph, series: pl.Series) -> pl.Series: ...
+def edgeset_to_list(g: Graph, series: pl.Series) -> pl.Series: ...
+def nodeset_expand(g: Graph, series: pl.Series, hops: int) -> pl.Series: ...
+def nodeset_boundary(g: Graph, series: pl.Series) -> pl.Series: ...
+def boundary_size(g: Graph, series: pl.Series) -> pl.Series: ...
+def wcc_labels(g: Graph, series: pl.Series) -> pl.Series: ...
+def scc_labels(g: Graph, series: pl.Series) -> pl.Series: ...
+def directed_statistics(g: Graph, clus: pl.DataFrame, resolution: float = 1.0) -> pl.DataFrame: ...
+def cut_statistics(g: Graph, clus: pl.DataFrame) -> pl.DataFrame: ...
+def cluster_component_coverage(g: Graph, clus: pl.DataFrame) -> pl.DataFrame: ...
+def component_coverage(g: Graph, clus: pl.DataFrame) -> pl.DataFrame: ...
+def centrality_statistics(
+    g: Graph,
+    clus: pl.DataFrame,
+    damping: float = 0.85,
+    tolerance: float = 1e-6,
+    max_iterations: int = 100,
+) -> pl.DataFrame: ...
+def betweenness_statistics(
+    g: Graph,
+    clus: pl.DataFrame,
+    samples: Optional[int] = None,
+    epsilon: Optional[float] = None,
+    delta: float = 0.1,
+    seed: int = 0,
+) -> pl.DataFrame: ...
+def quality_statistics(g: Graph, clus: pl.DataFrame, progress: Progress = None) -> pl.DataFrame: ...
+def null_significance(
+    g: Graph,
+    n: pl.Series,
+    m: pl.Series,
+    vol: pl.Series,
+    null: str = "configuration",
+    samples: Optional[int] = None,
+    seed: int = 0,
+) -> pl.DataFrame: ...
+def erdos_renyi(n: int, p: float, seed: int = 0) -> Graph: ...
+def configuration_model(degrees: Sequence[int], seed: int = 0) -> Graph: ...
+def stochastic_block_model(
+    sizes: Sequence[int], probabilities: Sequence[Sequence[float]], seed: int = 0
+) -> Tuple[Graph, pl.DataFrame]: ...
+def lfr(
+    n: int = 1000,
+    average_degree: float = 10.0,
+    max_degree: int = 50,
+    mixing: float = 0.1,
+    degree_exponent: float = 2.5,
+    community_exponent: float = 1.5,
+    min_community: Optional[int] = None,
+    max_community: Optional[int] = None,
+    seed: int = 0,
+) -> Tuple[Graph, pl.DataFrame]: ...
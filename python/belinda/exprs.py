@@ -1,3 +1,4 @@
+from contextlib import contextmanager
 from polars import col, when
 from polars import Expr
 import polars as pl
@@ -30,6 +31,28 @@ def conductance(self):
     )
 
 
+def significance(self, null="configuration", samples=None, seed=0):
+    """The z-score and p-value of `m` under the "configuration" or "er" null model, as a
+    `significance` struct with fields `z_score` and `p_value`, simulated from `samples`
+    random graphs if given. Computing both at once runs the simulation only once."""
+    return pl.map(
+        [col("n"), col("m"), vol],
+        lambda s: null_significance(self, s[0], s[1], s[2], null, samples, seed).to_struct(
+            "significance"
+        ),
+    ).alias("significance")
+
+
+def z_score(self, null="configuration", samples=None, seed=0):
+    """Z-score of `m` under the "configuration" or "er" null model, simulated from `samples` random graphs if given."""
+    return significance(self, null, samples, seed).struct.field("z_score").alias("z_score")
+
+
+def p_value(self, null="configuration", samples=None, seed=0):
+    """P-value of at least `m` internal edges under the "configuration" or "er" null model."""
+    return significance(self, null, samples, seed).struct.field("p_value").alias("p_value")
+
+
 def node_coverage(self, overlap=False):
     a = "node_coverage"
     if overlap:
@@ -74,14 +97,25 @@ def peek(graph, clustering, overlap=False, statistics=[pl.col('n')]):
 
 def write_membership(graph, clustering, filepath):
     df = graph.nodes(clustering)
-    with open(filepath, "w+") as fh:
+    with open_compressed(str(filepath)) as fh:
         for n, lbls in zip(df.get_column("node"), df.get_column("labels")):
-            for l in lbls:
-                fh.write(f"{n}\t{l}\n")
+            fh.write("".join(f"{n}\t{l}\n" for l in lbls).encode())
 
 def write_json(graph, clustering, filepath):
     clus = clustering.with_column(pl.col('nodes').set.flatten(graph).alias('nodes'))
-    clus.write_ndjson(filepath)
+    with open_compressed(str(filepath)) as fh:
+        clus.write_ndjson(fh)
+
+@contextmanager
+def threads(n):
+    """Run belinda computations started from this thread inside the block on `n` threads.
+
+    Other threads keep their own setting, so concurrent blocks do not interfere."""
+    push_nthreads(n)
+    try:
+        yield
+    finally:
+        pop_nthreads()
 
 setattr(Graph, "modularity", modularity)
 setattr(Graph, "cpm", lambda self, r: cpm(r))
@@ -89,9 +123,19 @@ setattr(
     Graph, "intra_edges", lambda self, exprs: exprs.map(lambda x: self.covered_edges(x))
 )
 setattr(Graph, "conductance", conductance)
+setattr(Graph, "significance", significance)
+setattr(Graph, "z_score", z_score)
+setattr(Graph, "p_value", p_value)
 setattr(Graph, "vol1", vol1)
 setattr(Graph, "cc", lambda self, exprs: exprs.map(lambda x: cc_labels(self, x)))
 setattr(Graph, "cc_size", lambda self, exprs: exprs.map(lambda x: cc_size(self, x)))
+setattr(
+    Graph,
+    "boundary_size",
+    lambda self: col("nodes").map(lambda x: boundary_size(self, x)).alias("boundary_size"),
+)
+setattr(Graph, "wcc", lambda self, exprs: exprs.map(lambda x: wcc_labels(self, x)))
+setattr(Graph, "scc", lambda self, exprs: exprs.map(lambda x: scc_labels(self, x)))
 setattr(
     Graph,
     "annotate_cc",
@@ -129,3 +173,15 @@ class EfficientSet:
 
     def flatten(self, g):
         return self._expr.map(lambda x: nodeset_to_list(g, x))
+
+    def boundary(self, g):
+        """The nodes outside each set that are adjacent to it."""
+        return self._expr.map(lambda x: nodeset_boundary(g, x))
+
+    def expand(self, g, hops=1):
+        """Grow each set by the nodes within `hops` hops of it."""
+        return self._expr.map(lambda x: nodeset_expand(g, x, hops))
+
+    def flatten_edges(self, g):
+        """Decode edge sets (e.g. from `g.intra_edges`) into lists of `{src, dst}` structs."""
+        return self._expr.map(lambda x: edgeset_to_list(g, x))
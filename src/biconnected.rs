@@ -0,0 +1,134 @@
+use roaring::{RoaringBitmap, RoaringTreemap};
+
+/// Biconnected components, bridges and articulation points of an undirected graph.
+/// Edges are identified by the ids of `Graph::edge_id`.
+#[derive(Debug, Clone)]
+pub struct Biconnectivity {
+    /// The biconnected component of every edge, indexed by edge id.
+    pub edge_components: Vec<u32>,
+    pub num_components: u32,
+    pub bridges: RoaringTreemap,
+    pub articulation_points: RoaringBitmap,
+}
+
+/// Hopcroft–Tarjan over `n` nodes and `m` edges, with an explicit stack so that deep
+/// graphs do not overflow. `neighbors` must be simple and symmetric.
+pub fn decompose<'a, N, E>(n: usize, m: usize, neighbors: N, edge_id: E) -> Biconnectivity
+where
+    N: Fn(usize) -> &'a [usize],
+    E: Fn(usize, usize) -> u64,
+{
+    let mut disc = vec![u32::MAX; n];
+    let mut low = vec![0u32; n];
+    let mut time = 0u32;
+    let mut edge_components = vec![u32::MAX; m];
+    let mut num_components = 0u32;
+    let mut bridges = RoaringTreemap::new();
+    let mut articulation_points = RoaringBitmap::new();
+    let mut edges: Vec<u64> = vec![];
+    for root in 0..n {
+        if disc[root] != u32::MAX {
+            continue;
+        }
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+        let mut root_children = 0;
+        // (node, position in its adjacency, id of the edge to its parent)
+        let mut calls: Vec<(usize, usize, u64)> = vec![(root, 0, u64::MAX)];
+        while let Some(&(u, pos, parent_edge)) = calls.last() {
+            let adj = neighbors(u);
+            if pos < adj.len() {
+                calls.last_mut().unwrap().1 += 1;
+                let v = adj[pos];
+                let e = edge_id(u, v);
+                if e == parent_edge {
+                    continue;
+                }
+                if disc[v] == u32::MAX {
+                    disc[v] = time;
+                    low[v] = time;
+                    time += 1;
+                    edges.push(e);
+                    calls.push((v, 0, e));
+                    if u == root {
+                        root_children += 1;
+                    }
+                } else if disc[v] < disc[u] {
+                    edges.push(e);
+                    low[u] = low[u].min(disc[v]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(p, _, _)) = calls.last() {
+                low[p] = low[p].min(low[u]);
+                if low[u] >= disc[p] {
+                    if p != root {
+                        articulation_points.insert(p as u32);
+                    }
+                    if low[u] > disc[p] {
+                        bridges.insert(parent_edge);
+                    }
+                    while let Some(e) = edges.pop() {
+                        edge_components[e as usize] = num_components;
+                        if e == parent_edge {
+                            break;
+                        }
+                    }
+                    num_components += 1;
+                }
+            }
+        }
+        if root_children >= 2 {
+            articulation_points.insert(root as u32);
+        }
+    }
+    Biconnectivity {
+        edge_components,
+        num_components,
+        bridges,
+        articulation_points,
+    }
+}
+
+/// A standalone adjacency with the edge numbering of `Graph::edge_id`, for decomposing
+/// induced subgraphs without assembling a `Graph`.
+pub struct Adjacency {
+    pub edges: Vec<Vec<usize>>,
+    acc: Vec<u64>,
+}
+
+impl Adjacency {
+    /// `edges` must be sorted, simple and symmetric.
+    pub fn new(edges: Vec<Vec<usize>>) -> Self {
+        let mut acc = Vec::with_capacity(edges.len() + 1);
+        let mut total = 0u64;
+        for (u, adj) in edges.iter().enumerate() {
+            acc.push(total);
+            total += adj.iter().filter(|&&v| v > u).count() as u64;
+        }
+        acc.push(total);
+        Adjacency { edges, acc }
+    }
+
+    pub fn m(&self) -> usize {
+        *self.acc.last().unwrap() as usize
+    }
+
+    pub fn edge_id(&self, u: usize, v: usize) -> u64 {
+        let (u, v) = (u.min(v), u.max(v));
+        let adj = &self.edges[u];
+        let start = adj.partition_point(|&w| w <= u);
+        self.acc[u] + (adj[start..].partition_point(|&w| w < v)) as u64
+    }
+
+    pub fn decompose(&self) -> Biconnectivity {
+        decompose(
+            self.edges.len(),
+            self.m(),
+            |u| self.edges[u].as_slice(),
+            |u, v| self.edge_id(u, v),
+        )
+    }
+}
@@ -0,0 +1,38 @@
+use aocluster::base;
+
+/// Assembles an `aocluster` graph in memory. `names[u]` is the original id of internal
+/// node `u` and `adj[u]` its neighbors by internal id. Adjacency lists must be symmetric
+/// and free of duplicates; they are sorted here as the rest of the crate expects.
+pub fn assemble(names: Vec<usize>, adj: Vec<Vec<usize>>) -> base::Graph {
+    let mut g = base::Graph::default();
+    let mut twice_m = 0;
+    for (u, (name, mut edges)) in names.into_iter().zip(adj).enumerate() {
+        let id = g.name_set.bi_onboard(name);
+        debug_assert_eq!(id, u, "duplicate node name {}", name);
+        edges.sort_unstable();
+        twice_m += edges.len();
+        g.nodes.push(base::Node { id, edges });
+    }
+    g.m_cache = twice_m / 2;
+    g
+}
+
+/// Assembles a graph from an undirected edge list over internal ids `0..names.len()`.
+/// Self-loops and repeated edges are dropped.
+pub fn assemble_from_edges<I>(names: Vec<usize>, edges: I) -> base::Graph
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let mut adj = vec![vec![]; names.len()];
+    for (u, v) in edges {
+        if u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for edges in adj.iter_mut() {
+        edges.sort_unstable();
+        edges.dedup();
+    }
+    assemble(names, adj)
+}
@@ -0,0 +1,348 @@
+use ahash::AHashSet;
+use aocluster::{
+    aoc::rayon::prelude::{IntoParallelIterator, ParallelIterator},
+    base,
+};
+use rand::{seq::index, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+/// Which centrality columns `Graph::nodes_df` adds after `degree`.
+#[derive(Debug, Clone)]
+pub struct CentralityColumns {
+    /// PageRank (`pagerank`) with this damping factor.
+    pub pagerank: Option<f64>,
+    /// Eigenvector centrality (`eigenvector`).
+    pub eigenvector: bool,
+    /// The number of other nodes within this many hops (`k_hop_degree`).
+    pub k_hop: Option<u32>,
+    /// Estimated betweenness (`betweenness`).
+    pub betweenness: Option<BetweennessSampling>,
+    /// Seed of the betweenness sampling.
+    pub seed: u64,
+    /// The iterations of PageRank and eigenvector centrality stop once the scores move
+    /// less than this in total (L1).
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for CentralityColumns {
+    fn default() -> Self {
+        CentralityColumns {
+            pagerank: None,
+            eigenvector: false,
+            k_hop: None,
+            betweenness: None,
+            seed: 0,
+            tolerance: 1e-6,
+            max_iterations: 100,
+        }
+    }
+}
+
+/// PageRank by power iteration, indexed by internal id and summing to 1. Isolated
+/// nodes spread their rank uniformly.
+pub fn pagerank(g: &base::Graph, damping: f64, tolerance: f64, max_iterations: usize) -> Vec<f64> {
+    let n = g.n();
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..max_iterations {
+        let dangling: f64 = (0..n)
+            .into_par_iter()
+            .filter(|&u| g.nodes[u].edges.is_empty())
+            .map(|u| rank[u])
+            .sum();
+        let teleport = (1.0 - damping + damping * dangling) / n as f64;
+        let next: Vec<f64> = (0..n)
+            .into_par_iter()
+            .map(|u| {
+                let inflow: f64 = g.nodes[u]
+                    .edges
+                    .iter()
+                    .map(|&v| rank[v] / g.nodes[v].degree() as f64)
+                    .sum();
+                teleport + damping * inflow
+            })
+            .collect();
+        let change: f64 = (0..n)
+            .into_par_iter()
+            .map(|u| (next[u] - rank[u]).abs())
+            .sum();
+        rank = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    rank
+}
+
+/// Eigenvector centrality by power iteration on `A + I` (which has the same leading
+/// eigenvector as `A` but does not oscillate on bipartite graphs), with unit L2 norm.
+pub fn eigenvector(g: &base::Graph, tolerance: f64, max_iterations: usize) -> Vec<f64> {
+    let n = g.n();
+    let mut x = vec![1.0 / (n as f64).sqrt(); n];
+    for _ in 0..max_iterations {
+        let mut next: Vec<f64> = (0..n)
+            .into_par_iter()
+            .map(|u| x[u] + g.nodes[u].edges.iter().map(|&v| x[v]).sum::<f64>())
+            .collect();
+        let norm = next.iter().map(|it| it * it).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            break;
+        }
+        next.iter_mut().for_each(|it| *it /= norm);
+        let change: f64 = (0..n).into_par_iter().map(|u| (next[u] - x[u]).abs()).sum();
+        x = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    x
+}
+
+/// The number of other nodes within `k` hops of every node.
+pub fn k_hop_degree(g: &base::Graph, k: u32) -> Vec<u32> {
+    (0..g.n())
+        .into_par_iter()
+        .map(|source| {
+            let mut seen = AHashSet::from_iter([source]);
+            let mut frontier = vec![source];
+            for _ in 0..k {
+                let mut next = vec![];
+                for u in frontier {
+                    for &v in &g.nodes[u].edges {
+                        if seen.insert(v) {
+                            next.push(v);
+                        }
+                    }
+                }
+                if next.is_empty() {
+                    break;
+                }
+                frontier = next;
+            }
+            (seen.len() - 1) as u32
+        })
+        .collect()
+}
+
+/// How `betweenness` samples shortest paths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BetweennessSampling {
+    /// Brandes' dependency accumulation from this many sources drawn without replacement,
+    /// scaled up; exact if at least the number of nodes.
+    Sources(usize),
+    /// Riondato–Kornaropoulos sampling of shortest paths between random pairs, with as
+    /// many samples as needed for every normalized score to be within `epsilon` of the
+    /// truth with probability at least `1 - delta`.
+    ErrorBound { epsilon: f64, delta: f64 },
+}
+
+impl BetweennessSampling {
+    /// Rejects parameters that would sample nothing or without end.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match *self {
+            BetweennessSampling::Sources(k) => {
+                anyhow::ensure!(k > 0, "expected at least one betweenness source")
+            }
+            BetweennessSampling::ErrorBound { epsilon, delta } => {
+                anyhow::ensure!(
+                    epsilon > 0.0 && epsilon.is_finite(),
+                    "epsilon must be positive, got {}",
+                    epsilon
+                );
+                anyhow::ensure!(
+                    delta > 0.0 && delta < 1.0,
+                    "delta must be in (0, 1), got {}",
+                    delta
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Estimated betweenness of every node, over unordered pairs of other nodes (the scale
+/// of exact unnormalized betweenness). The sampled sources or paths are determined by
+/// `seed` regardless of the number of threads, but the scores are summed in parallel,
+/// so they can differ in the last bits between runs.
+pub fn betweenness(
+    g: &base::Graph,
+    sampling: BetweennessSampling,
+    seed: u64,
+) -> anyhow::Result<Vec<f64>> {
+    sampling.validate()?;
+    let n = g.n();
+    if n < 3 {
+        return Ok(vec![0.0; n]);
+    }
+    Ok(match sampling {
+        BetweennessSampling::Sources(k) => {
+            let k = k.min(n);
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let sources = index::sample(&mut rng, n, k).into_vec();
+            let scale = n as f64 / k as f64 / 2.0;
+            sources
+                .into_par_iter()
+                .fold(
+                    || (ShortestPaths::new(n), vec![0.0; n]),
+                    |(mut paths, mut scores), s| {
+                        paths.search(g, s, None);
+                        paths.accumulate(g, &mut scores, scale);
+                        (paths, scores)
+                    },
+                )
+                .map(|(_, scores)| scores)
+                .reduce(|| vec![0.0; n], add_scores)
+        }
+        BetweennessSampling::ErrorBound { epsilon, delta } => {
+            let diameter = vertex_diameter_bound(g) as f64;
+            let r = (0.5 / (epsilon * epsilon)
+                * ((diameter - 2.0).max(1.0).log2().floor() + 1.0 + (1.0 / delta).ln()))
+            .ceil() as u64;
+            // each sample adds 1 / r to the normalized score over ordered pairs
+            let scale = (n * (n - 1)) as f64 / 2.0 / r as f64;
+            (0..r)
+                .into_par_iter()
+                .fold(
+                    || (ShortestPaths::new(n), vec![0.0; n]),
+                    |(mut paths, mut scores), i| {
+                        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                        rng.set_stream(i);
+                        let s = rng.gen_range(0..n);
+                        let t = (s + rng.gen_range(1..n)) % n;
+                        paths.search(g, s, Some(t));
+                        paths.sample_path(g, t, &mut rng, &mut scores, scale);
+                        (paths, scores)
+                    },
+                )
+                .map(|(_, scores)| scores)
+                .reduce(|| vec![0.0; n], add_scores)
+        }
+    })
+}
+
+fn add_scores(a: Vec<f64>, b: Vec<f64>) -> Vec<f64> {
+    a.iter().zip(b).map(|(a, b)| a + b).collect()
+}
+
+/// An upper bound on the number of nodes of any shortest path: one breadth-first search
+/// per component gives an eccentricity `e`, and no path there has more than `2e + 1`.
+fn vertex_diameter_bound(g: &base::Graph) -> usize {
+    let mut dist = vec![u32::MAX; g.n()];
+    let mut bound = 1;
+    for root in 0..g.n() {
+        if dist[root] != u32::MAX {
+            continue;
+        }
+        dist[root] = 0;
+        let mut queue = VecDeque::from([root]);
+        let mut ecc = 0;
+        while let Some(u) = queue.pop_front() {
+            ecc = dist[u];
+            for &v in &g.nodes[u].edges {
+                if dist[v] == u32::MAX {
+                    dist[v] = dist[u] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        bound = bound.max(2 * ecc as usize + 1);
+    }
+    bound
+}
+
+/// Single-source shortest path counts, reusable across searches on the same graph.
+struct ShortestPaths {
+    dist: Vec<u32>,
+    sigma: Vec<f64>,
+    delta: Vec<f64>,
+    /// Nodes reached by the last search, in nondecreasing distance.
+    order: Vec<usize>,
+}
+
+impl ShortestPaths {
+    fn new(n: usize) -> Self {
+        ShortestPaths {
+            dist: vec![u32::MAX; n],
+            sigma: vec![0.0; n],
+            delta: vec![0.0; n],
+            order: vec![],
+        }
+    }
+
+    /// Breadth-first search from `s`, stopping after the level of `target` if given.
+    fn search(&mut self, g: &base::Graph, s: usize, target: Option<usize>) {
+        for &u in &self.order {
+            self.dist[u] = u32::MAX;
+            self.sigma[u] = 0.0;
+            self.delta[u] = 0.0;
+        }
+        self.order.clear();
+        self.dist[s] = 0;
+        self.sigma[s] = 1.0;
+        self.order.push(s);
+        let mut head = 0;
+        while head < self.order.len() {
+            let u = self.order[head];
+            head += 1;
+            if matches!(target, Some(t) if self.dist[t] < self.dist[u]) {
+                break;
+            }
+            for &v in &g.nodes[u].edges {
+                if self.dist[v] == u32::MAX {
+                    self.dist[v] = self.dist[u] + 1;
+                    self.order.push(v);
+                }
+                if self.dist[v] == self.dist[u] + 1 {
+                    self.sigma[v] += self.sigma[u];
+                }
+            }
+        }
+    }
+
+    /// Adds `scale` times the dependencies of the source on every other node to `scores`.
+    fn accumulate(&mut self, g: &base::Graph, scores: &mut [f64], scale: f64) {
+        for &w in self.order.iter().skip(1).rev() {
+            let mut delta = 0.0;
+            for &v in &g.nodes[w].edges {
+                if self.dist[v] == self.dist[w] + 1 {
+                    delta += self.sigma[w] / self.sigma[v] * (1.0 + self.delta[v]);
+                }
+            }
+            self.delta[w] = delta;
+            scores[w] += scale * delta;
+        }
+    }
+
+    /// Walks back a uniformly random shortest path from `t` to the source of the last
+    /// search, adding `scale` to the score of every inner node.
+    fn sample_path<R: Rng>(
+        &self,
+        g: &base::Graph,
+        t: usize,
+        rng: &mut R,
+        scores: &mut [f64],
+        scale: f64,
+    ) {
+        if self.dist[t] == u32::MAX {
+            return;
+        }
+        let mut u = t;
+        while self.dist[u] > 1 {
+            let mut pick = rng.gen_range(0.0..self.sigma[u]);
+            let mut predecessor = u;
+            for &v in &g.nodes[u].edges {
+                if self.dist[v] != u32::MAX && self.dist[v] + 1 == self.dist[u] {
+                    // the last predecessor also absorbs rounding errors of `pick`
+                    predecessor = v;
+                    pick -= self.sigma[v];
+                    if pick < 0.0 {
+                        break;
+                    }
+                }
+            }
+            u = predecessor;
+            scores[u] += scale;
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use polars::prelude::*;
+
+use crate::{
+    df::{iter_roaring, EfficientSet},
+    graph::Graph,
+    stats::clusdf_to_membership,
+};
+
+/// A clustering as one row per cluster: `label`, the `nodes` (serialized `EfficientSet`s
+/// of internal ids) and the statistics computed on load (`n`, `m`, `c`, `mcd`).
+/// This is the same frame the Python readers return.
+#[derive(Clone, Debug)]
+pub struct ClusterFrame {
+    df: DataFrame,
+}
+
+impl ClusterFrame {
+    /// Wraps a data frame, which must have at least the `label` and `nodes` columns.
+    pub fn new(df: DataFrame) -> anyhow::Result<Self> {
+        df.column("label")?;
+        if df.column("nodes")?.dtype() != &DataType::Binary {
+            anyhow::bail!("`nodes` must be a column of serialized node sets");
+        }
+        Ok(ClusterFrame { df })
+    }
+
+    pub fn len(&self) -> usize {
+        self.df.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.df.height() == 0
+    }
+
+    pub fn labels(&self) -> &Series {
+        self.df.column("label").unwrap()
+    }
+
+    pub fn nodes(&self) -> &Series {
+        self.df.column("nodes").unwrap()
+    }
+
+    pub fn column(&self, name: &str) -> anyhow::Result<&Series> {
+        Ok(self.df.column(name)?)
+    }
+
+    /// The node set of every cluster, in row order.
+    pub fn node_sets(&self) -> impl Iterator<Item = EfficientSet> + '_ {
+        iter_roaring(self.nodes())
+    }
+
+    /// A label in `0..k` for every node of `g`, overlapping nodes keeping their first
+    /// cluster and uncovered nodes getting fresh labels.
+    pub fn membership(&self, g: &Graph) -> anyhow::Result<Vec<u32>> {
+        clusdf_to_membership(g, self.nodes())
+    }
+
+    pub fn df(&self) -> &DataFrame {
+        &self.df
+    }
+
+    pub fn into_df(self) -> DataFrame {
+        self.df
+    }
+}
+
+impl From<ClusterFrame> for DataFrame {
+    fn from(clus: ClusterFrame) -> Self {
+        clus.df
+    }
+}
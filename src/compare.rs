@@ -0,0 +1,122 @@
+use ahash::AHashMap;
+use polars::df;
+use polars::prelude::*;
+
+/// Agreement between two clusterings, measured on the nodes they both cover (`NaN` if none).
+/// Nodes in several clusters are attributed to the first one they appear in.
+#[derive(Debug, Clone)]
+pub struct ClusteringComparison {
+    pub clusters_a: usize,
+    pub clusters_b: usize,
+    pub nodes_a: usize,
+    pub nodes_b: usize,
+    pub shared_nodes: usize,
+    /// Normalized mutual information, arithmetic normalization.
+    pub nmi: f64,
+    /// Adjusted Rand index.
+    pub ari: f64,
+}
+
+impl ClusteringComparison {
+    pub fn to_df(&self) -> anyhow::Result<DataFrame> {
+        Ok(df!(
+            "clusters_a" => [self.clusters_a as u64],
+            "clusters_b" => [self.clusters_b as u64],
+            "nodes_a" => [self.nodes_a as u64],
+            "nodes_b" => [self.nodes_b as u64],
+            "shared_nodes" => [self.shared_nodes as u64],
+            "nmi" => [self.nmi],
+            "ari" => [self.ari],
+        )?)
+    }
+}
+
+/// Maps every node to a dense cluster index, returning the number of clusters as well.
+fn dense_membership(table: &DataFrame) -> anyhow::Result<(AHashMap<u32, u32>, usize)> {
+    let labels = table.column("label")?.cast(&DataType::Utf8)?;
+    let nodes = table.column("node")?.cast(&DataType::UInt32)?;
+    let mut ids: AHashMap<&str, u32> = AHashMap::new();
+    let mut membership = AHashMap::new();
+    for (node, label) in nodes.u32()?.into_iter().zip(labels.utf8()?) {
+        if let (Some(node), Some(label)) = (node, label) {
+            let next = ids.len() as u32;
+            let id = *ids.entry(label).or_insert(next);
+            membership.entry(node).or_insert(id);
+        }
+    }
+    Ok((membership, ids.len()))
+}
+
+fn choose2(x: u64) -> f64 {
+    (x as f64) * (x.saturating_sub(1) as f64) / 2.0
+}
+
+/// Compares two membership tables with `node` and `label` columns
+/// (see `io::read_membership_table`).
+pub fn compare_memberships(a: &DataFrame, b: &DataFrame) -> anyhow::Result<ClusteringComparison> {
+    let (ma, ka) = dense_membership(a)?;
+    let (mb, kb) = dense_membership(b)?;
+    let mut joint: AHashMap<(u32, u32), u64> = AHashMap::new();
+    let mut rows = vec![0u64; ka];
+    let mut cols = vec![0u64; kb];
+    let mut shared = 0u64;
+    for (node, &ca) in &ma {
+        if let Some(&cb) = mb.get(node) {
+            *joint.entry((ca, cb)).or_insert(0) += 1;
+            rows[ca as usize] += 1;
+            cols[cb as usize] += 1;
+            shared += 1;
+        }
+    }
+    let total = shared as f64;
+    let entropy = |counts: &[u64]| -> f64 {
+        counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f64 / total;
+                -p * p.ln()
+            })
+            .sum()
+    };
+    let (ha, hb) = (entropy(&rows), entropy(&cols));
+    let mutual: f64 = joint
+        .iter()
+        .map(|(&(ca, cb), &c)| {
+            let c = c as f64;
+            c / total * (total * c / (rows[ca as usize] as f64 * cols[cb as usize] as f64)).ln()
+        })
+        .sum();
+    let nmi = if shared == 0 {
+        f64::NAN
+    } else if ha + hb > 0.0 {
+        2.0 * mutual / (ha + hb)
+    } else {
+        1.0
+    };
+    let index: f64 = joint.values().map(|&c| choose2(c)).sum();
+    let sum_a: f64 = rows.iter().map(|&c| choose2(c)).sum();
+    let sum_b: f64 = cols.iter().map(|&c| choose2(c)).sum();
+    let expected = if shared > 1 {
+        sum_a * sum_b / choose2(shared)
+    } else {
+        0.0
+    };
+    let max = (sum_a + sum_b) / 2.0;
+    let ari = if shared == 0 {
+        f64::NAN
+    } else if max > expected {
+        (index - expected) / (max - expected)
+    } else {
+        1.0
+    };
+    Ok(ClusteringComparison {
+        clusters_a: ka,
+        clusters_b: kb,
+        nodes_a: ma.len(),
+        nodes_b: mb.len(),
+        shared_nodes: shared as usize,
+        nmi,
+        ari,
+    })
+}
@@ -0,0 +1,172 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Cursor, Read, Write},
+    path::Path,
+};
+
+use bzip2::{read::MultiBzDecoder, write::BzEncoder};
+use flate2::{read::MultiGzDecoder, write::GzEncoder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Detects the compression of a stream from its first bytes.
+    pub fn from_magic(head: &[u8]) -> Self {
+        match head {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Compression::Zstd,
+            [b'B', b'Z', b'h', ..] => Compression::Bzip2,
+            _ => Compression::None,
+        }
+    }
+
+    /// The compression implied by the extension of `path`, used for writing.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|it| it.to_str()) {
+            Some("gz" | "gzip") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            Some("bz2") => Compression::Bzip2,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// `path` without a compression extension, e.g. `clus.json` for `clus.json.gz`.
+pub fn strip_extension(path: &Path) -> &Path {
+    match Compression::from_path(path) {
+        Compression::None => path,
+        _ => path.file_stem().map(Path::new).unwrap_or(path),
+    }
+}
+
+/// Opens `path` for streaming reads, decompressing it if its magic bytes say so.
+pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Box<dyn BufRead + Send>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let compression = Compression::from_magic(reader.fill_buf()?);
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)),
+        Compression::Bzip2 => Box::new(BufReader::new(MultiBzDecoder::new(reader))),
+    })
+}
+
+/// Bytes of decompressed data handed to a polars reader at a time.
+const CHUNK_SIZE: usize = 64 << 20;
+
+/// Data for the polars readers, which need random access: the file itself when it is
+/// not compressed, otherwise its decompressed content in chunks of whole lines, so that
+/// only one chunk is held in memory besides the rows parsed so far.
+pub enum Source {
+    File(File),
+    Chunks(LineChunks),
+}
+
+impl Source {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let mut head = [0u8; 4];
+        let mut file = File::open(path)?;
+        let read = file.read(&mut head)?;
+        if Compression::from_magic(&head[..read]) == Compression::None {
+            return Ok(Source::File(File::open(path)?));
+        }
+        Ok(Source::Chunks(LineChunks {
+            reader: open(path)?,
+            chunk_size: CHUNK_SIZE,
+        }))
+    }
+}
+
+/// A stream split into chunks of about `chunk_size` bytes, each ending at a line break
+/// (or at the end of the stream).
+pub struct LineChunks {
+    reader: Box<dyn BufRead + Send>,
+    chunk_size: usize,
+}
+
+impl Iterator for LineChunks {
+    type Item = io::Result<Cursor<Vec<u8>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = vec![];
+        while buf.len() < self.chunk_size {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(e) => return Some(Err(e)),
+            };
+            if available.is_empty() {
+                break;
+            }
+            let take = available.len().min(self.chunk_size - buf.len());
+            buf.extend_from_slice(&available[..take]);
+            self.reader.consume(take);
+        }
+        if !buf.is_empty() && buf.last() != Some(&b'\n') {
+            if let Err(e) = self.reader.read_until(b'\n', &mut buf) {
+                return Some(Err(e));
+            }
+        }
+        (!buf.is_empty()).then(|| Ok(Cursor::new(buf)))
+    }
+}
+
+/// A file opened by `create`. Compressed streams end with a trailer that is only
+/// written by `finish`, which also reports the errors that dropping would swallow.
+pub enum Writer {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+    Bzip2(BzEncoder<BufWriter<File>>),
+}
+
+impl Writer {
+    pub fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            Writer::Plain(file) => file,
+            Writer::Gzip(encoder) => encoder.finish()?,
+            Writer::Zstd(encoder) => encoder.finish()?,
+            Writer::Bzip2(encoder) => encoder.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Writer::Plain(file) => file.write(buf),
+            Writer::Gzip(encoder) => encoder.write(buf),
+            Writer::Zstd(encoder) => encoder.write(buf),
+            Writer::Bzip2(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Writer::Plain(file) => file.flush(),
+            Writer::Gzip(encoder) => encoder.flush(),
+            Writer::Zstd(encoder) => encoder.flush(),
+            Writer::Bzip2(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Creates `path` for writing, compressing according to its extension. The result must
+/// be `finish`ed.
+pub fn create<P: AsRef<Path>>(path: P) -> anyhow::Result<Writer> {
+    let compression = Compression::from_path(&path);
+    let file = BufWriter::new(File::create(path)?);
+    Ok(match compression {
+        Compression::None => Writer::Plain(file),
+        Compression::Gzip => Writer::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+        Compression::Zstd => Writer::Zstd(zstd::Encoder::new(file, 0)?),
+        Compression::Bzip2 => Writer::Bzip2(BzEncoder::new(file, bzip2::Compression::default())),
+    })
+}
@@ -17,6 +17,20 @@ impl EfficientSet {
             EfficientSet::BigSet(set) => set.len() as u64,
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            EfficientSet::SmallSet(set) => set.is_empty(),
+            EfficientSet::BigSet(set) => set.is_empty(),
+        }
+    }
+
+    pub fn iter(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        match self {
+            EfficientSet::SmallSet(set) => Box::new(set.iter().map(|it| it as u64)),
+            EfficientSet::BigSet(set) => Box::new(set.iter()),
+        }
+    }
 }
 
 pub trait VecEfficientSet {
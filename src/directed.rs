@@ -0,0 +1,198 @@
+use aocluster::aoc::rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use roaring::RoaringBitmap;
+
+/// Arcs of a directed graph as out- and in-adjacency lists, over the internal ids of
+/// the undirected view held by `Graph`.
+pub struct Directed {
+    pub out_adj: Vec<Vec<u32>>,
+    pub in_adj: Vec<Vec<u32>>,
+    m: u64,
+}
+
+/// Component labels of every node, and the size of every component.
+#[derive(Debug, Clone)]
+pub struct ComponentLabels {
+    pub labels: Vec<u32>,
+    pub sizes: Vec<u32>,
+}
+
+impl ComponentLabels {
+    /// Relabels `roots` (any representative per component) to `0..k` by first appearance.
+    fn from_roots(roots: &[u32]) -> Self {
+        let mut dense = vec![u32::MAX; roots.len()];
+        let mut sizes = vec![];
+        let labels = roots
+            .iter()
+            .map(|&r| {
+                if dense[r as usize] == u32::MAX {
+                    dense[r as usize] = sizes.len() as u32;
+                    sizes.push(0);
+                }
+                let label = dense[r as usize];
+                sizes[label as usize] += 1;
+                label
+            })
+            .collect();
+        ComponentLabels { labels, sizes }
+    }
+}
+
+impl Directed {
+    /// Builds the adjacency of `n` nodes from arcs free of duplicates and self-loops.
+    pub fn from_arcs(n: usize, arcs: &[(usize, usize)]) -> Self {
+        let mut out_adj = vec![vec![]; n];
+        let mut in_adj = vec![vec![]; n];
+        for &(u, v) in arcs {
+            out_adj[u].push(v as u32);
+            in_adj[v].push(u as u32);
+        }
+        for adj in out_adj.iter_mut().chain(in_adj.iter_mut()) {
+            adj.sort_unstable();
+        }
+        Directed {
+            out_adj,
+            in_adj,
+            m: arcs.len() as u64,
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.out_adj.len()
+    }
+
+    /// The number of arcs.
+    pub fn m(&self) -> u64 {
+        self.m
+    }
+
+    /// Weakly connected components.
+    pub fn weak_components(&self) -> ComponentLabels {
+        let mut parent: Vec<u32> = (0..self.n() as u32).collect();
+        fn find(parent: &mut [u32], mut u: u32) -> u32 {
+            while parent[u as usize] != u {
+                parent[u as usize] = parent[parent[u as usize] as usize];
+                u = parent[u as usize];
+            }
+            u
+        }
+        for (u, adj) in self.out_adj.iter().enumerate() {
+            for &v in adj {
+                let (a, b) = (find(&mut parent, u as u32), find(&mut parent, v));
+                if a != b {
+                    parent[a.max(b) as usize] = a.min(b);
+                }
+            }
+        }
+        let roots = (0..self.n() as u32)
+            .map(|u| find(&mut parent, u))
+            .collect::<Vec<_>>();
+        ComponentLabels::from_roots(&roots)
+    }
+
+    /// Strongly connected components (iterative Tarjan).
+    pub fn strong_components(&self) -> ComponentLabels {
+        let n = self.n();
+        let mut index = vec![u32::MAX; n];
+        let mut low = vec![0u32; n];
+        let mut on_stack = vec![false; n];
+        let mut root = vec![u32::MAX; n];
+        let mut stack: Vec<u32> = vec![];
+        let mut next = 0u32;
+        for s in 0..n as u32 {
+            if index[s as usize] != u32::MAX {
+                continue;
+            }
+            // (node, position in its out-adjacency)
+            let mut calls: Vec<(u32, usize)> = vec![(s, 0)];
+            index[s as usize] = next;
+            low[s as usize] = next;
+            next += 1;
+            stack.push(s);
+            on_stack[s as usize] = true;
+            while let Some(&(u, pos)) = calls.last() {
+                let adj = &self.out_adj[u as usize];
+                if pos < adj.len() {
+                    let v = adj[pos];
+                    calls.last_mut().unwrap().1 += 1;
+                    if index[v as usize] == u32::MAX {
+                        index[v as usize] = next;
+                        low[v as usize] = next;
+                        next += 1;
+                        stack.push(v);
+                        on_stack[v as usize] = true;
+                        calls.push((v, 0));
+                    } else if on_stack[v as usize] {
+                        low[u as usize] = low[u as usize].min(index[v as usize]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent as usize] = low[parent as usize].min(low[u as usize]);
+                }
+                if low[u as usize] == index[u as usize] {
+                    while let Some(w) = stack.pop() {
+                        on_stack[w as usize] = false;
+                        root[w as usize] = u;
+                        if w == u {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        ComponentLabels::from_roots(&root)
+    }
+
+    /// Per cluster: internal arcs, arcs leaving (out-cut) and entering (in-cut), and the
+    /// sums of out- and in-degrees.
+    pub fn cluster_arcs(&self, clusters: &[RoaringBitmap]) -> Vec<DirectedClusterArcs> {
+        clusters
+            .into_par_iter()
+            .map(|nodes| {
+                let mut stats = DirectedClusterArcs::default();
+                for u in nodes.iter() {
+                    let out = &self.out_adj[u as usize];
+                    let inc = &self.in_adj[u as usize];
+                    let internal = out.iter().filter(|&&v| nodes.contains(v)).count() as u64;
+                    let entering = inc.iter().filter(|&&v| !nodes.contains(v)).count() as u64;
+                    stats.internal += internal;
+                    stats.out_cut += out.len() as u64 - internal;
+                    stats.in_cut += entering;
+                    stats.out_volume += out.len() as u64;
+                    stats.in_volume += inc.len() as u64;
+                }
+                stats
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectedClusterArcs {
+    pub internal: u64,
+    pub out_cut: u64,
+    pub in_cut: u64,
+    pub out_volume: u64,
+    pub in_volume: u64,
+}
+
+impl DirectedClusterArcs {
+    /// Leicht–Newman directed modularity contribution, for a graph of `m` arcs.
+    pub fn modularity(&self, m: u64, resolution: f64) -> f64 {
+        let m = m as f64;
+        self.internal as f64 / m
+            - resolution * (self.out_volume as f64) * (self.in_volume as f64) / (m * m)
+    }
+
+    /// Cut arcs in both directions over the smaller of the two sides' total degree.
+    pub fn conductance(&self, m: u64) -> f64 {
+        let volume = self.out_volume + self.in_volume;
+        let denominator = volume.min(2 * m - volume);
+        if denominator == 0 {
+            0.0
+        } else {
+            (self.out_cut + self.in_cut) as f64 / denominator as f64
+        }
+    }
+}
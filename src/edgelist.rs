@@ -0,0 +1,203 @@
+use ahash::{AHashMap, AHashSet};
+use anyhow::Context;
+use aocluster::base;
+use std::io::BufRead;
+
+use crate::{builder, directed::Directed};
+
+/// What to do with an edge the graph cannot hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Skip it, counting it in the `GraphReadReport`.
+    Drop,
+    /// Fail the whole read.
+    Error,
+}
+
+impl std::str::FromStr for Policy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "drop" => Ok(Policy::Drop),
+            "error" => Ok(Policy::Error),
+            _ => Err(anyhow::anyhow!(
+                "Unknown policy: {} (expected drop or error)",
+                s
+            )),
+        }
+    }
+}
+
+/// How directed input lines become undirected edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetrize {
+    /// `u v` alone is enough for the edge.
+    Union,
+    /// Both `u v` and `v u` must be present.
+    Mutual,
+}
+
+impl std::str::FromStr for Symmetrize {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "union" => Ok(Symmetrize::Union),
+            "mutual" => Ok(Symmetrize::Mutual),
+            _ => Err(anyhow::anyhow!(
+                "Unknown symmetrization: {} (expected union or mutual)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphReadOptions {
+    /// Field separator, any run of whitespace if `None`.
+    pub delimiter: Option<char>,
+    /// Lines starting with this prefix (after leading whitespace) are skipped.
+    pub comment: Option<String>,
+    /// Skip the first line that is neither blank nor a comment.
+    pub skip_header: bool,
+    /// Zero-based indices of the source and target columns.
+    pub columns: (usize, usize),
+    pub self_loops: Policy,
+    /// Applies to repeated `u v` lines, and to `v u` after `u v` unless the direction
+    /// matters (`directed`, or `Symmetrize::Mutual`).
+    pub duplicates: Policy,
+    pub symmetrize: Symmetrize,
+    /// Also keep the arcs, in addition to the undirected view every statistic runs on.
+    pub directed: bool,
+}
+
+impl Default for GraphReadOptions {
+    fn default() -> Self {
+        GraphReadOptions {
+            delimiter: None,
+            comment: Some("#".to_string()),
+            skip_header: false,
+            columns: (0, 1),
+            self_loops: Policy::Drop,
+            duplicates: Policy::Drop,
+            symmetrize: Symmetrize::Union,
+            directed: false,
+        }
+    }
+}
+
+/// How many lines of an edge list were read, and how many were skipped for each reason.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphReadReport {
+    pub lines: u64,
+    pub blank: u64,
+    pub comments: u64,
+    pub header: u64,
+    pub self_loops: u64,
+    pub duplicates: u64,
+    /// Edges dropped by `Symmetrize::Mutual` for lacking the reverse direction.
+    pub unreciprocated: u64,
+}
+
+impl GraphReadReport {
+    pub fn skipped(&self) -> u64 {
+        self.blank
+            + self.comments
+            + self.header
+            + self.self_loops
+            + self.duplicates
+            + self.unreciprocated
+    }
+}
+
+/// Parses an edge list of integer node ids, numbering nodes by first appearance.
+/// Columns other than `options.columns` are ignored. The arcs are returned as well
+/// if `options.directed` is set.
+pub fn read_edgelist<R: BufRead>(
+    reader: R,
+    options: &GraphReadOptions,
+) -> anyhow::Result<(base::Graph, GraphReadReport, Option<Directed>)> {
+    let mut report = GraphReadReport::default();
+    let mut ids: AHashMap<usize, usize> = AHashMap::new();
+    let mut names = vec![];
+    let mut edges = vec![];
+    let mut header_pending = options.skip_header;
+    let (src, dst) = options.columns;
+    let undirected = !options.directed && options.symmetrize == Symmetrize::Union;
+    for (lineno, line) in reader.lines().enumerate() {
+        let line = line?;
+        report.lines += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            report.blank += 1;
+            continue;
+        }
+        if matches!(&options.comment, Some(prefix) if trimmed.starts_with(prefix.as_str())) {
+            report.comments += 1;
+            continue;
+        }
+        if header_pending {
+            header_pending = false;
+            report.header += 1;
+            continue;
+        }
+        let fields: Vec<&str> = match options.delimiter {
+            Some(delimiter) => trimmed.split(delimiter).map(str::trim).collect(),
+            None => trimmed.split_whitespace().collect(),
+        };
+        let (u, v) = match (fields.get(src), fields.get(dst)) {
+            (Some(u), Some(v)) => (*u, *v),
+            _ => anyhow::bail!(
+                "line {}: expected at least {} columns",
+                lineno + 1,
+                src.max(dst) + 1
+            ),
+        };
+        let mut id = |name: &str| -> anyhow::Result<usize> {
+            let name: usize = name
+                .parse()
+                .with_context(|| format!("line {}: invalid node id {:?}", lineno + 1, name))?;
+            Ok(*ids.entry(name).or_insert_with(|| {
+                names.push(name);
+                names.len() - 1
+            }))
+        };
+        let (u, v) = (id(u)?, id(v)?);
+        if u == v {
+            if options.self_loops == Policy::Error {
+                anyhow::bail!("line {}: self-loop on node {}", lineno + 1, names[u]);
+            }
+            report.self_loops += 1;
+            continue;
+        }
+        if undirected {
+            edges.push((u.min(v), u.max(v)));
+        } else {
+            edges.push((u, v));
+        }
+    }
+    edges.sort_unstable();
+    if options.duplicates == Policy::Error {
+        if let Some(w) = edges.windows(2).find(|w| w[0] == w[1]) {
+            let (u, v) = w[0];
+            anyhow::bail!("duplicate edge {} {}", names[u], names[v]);
+        }
+    }
+    let before = edges.len();
+    edges.dedup();
+    report.duplicates = (before - edges.len()) as u64;
+    // symmetrizing only shapes the undirected view, the directed graph keeps every arc
+    let directed = if options.directed {
+        Some(Directed::from_arcs(names.len(), &edges))
+    } else {
+        None
+    };
+    if options.symmetrize == Symmetrize::Mutual {
+        let directed: AHashSet<(usize, usize)> = edges.iter().copied().collect();
+        let before = edges.len();
+        edges.retain(|&(u, v)| directed.contains(&(v, u)));
+        report.unreciprocated = (before - edges.len()) as u64;
+    }
+    Ok((builder::assemble_from_edges(names, edges), report, directed))
+}
@@ -1,551 +1,868 @@
-use aocluster::{
-    alg::{self, CCLabels},
-    aoc::rayon::{
-        self,
-        prelude::{
-            IndexedParallelIterator, IntoParallelIterator, ParallelBridge, ParallelIterator,
-        },
-    },
-    belinda::{
-        EnrichedGraph,
-    },
-};
 use itertools::Itertools;
 use polars::prelude::*;
-use std::io::Write;
-use polars::{df, export::once_cell::sync::OnceCell};
-
 use pyo3::{
+    exceptions::PyValueError,
     prelude::*,
+    types::{PyBytes, PyDict},
 };
-use roaring::{MultiOps, RoaringBitmap, RoaringTreemap};
-use std::{path::Path, sync::Arc, io::BufWriter, fs::File};
+use roaring::{RoaringBitmap, RoaringTreemap};
+use std::io::Write;
 
 use crate::{
-    df::{build_series_from_sets, iter_roaring, EfficientSet, VecEfficientSet},
+    centrality::{BetweennessSampling, CentralityColumns},
+    compress,
+    df::{build_series_from_sets, deserialize_set, iter_roaring, serialize_set, VecEfficientSet},
+    edgelist::{GraphReadOptions, Policy, Symmetrize},
     ffi::{self, translate_df},
+    generators::{self, LfrConfig},
+    graph::Graph,
+    io::{read_json, read_membership_file, read_membership_series, SingletonMode},
+    leiden::{LeidenConfig, Objective},
+    logging,
+    null_model::NullModel,
+    pool,
+    progress::ProgressSpec,
+    stats,
 };
 
+/// Sets the number of threads used by belinda, re-reading `BELINDA_NUM_THREADS` if `None`.
+#[pyfunction(nthreads = "None")]
+pub fn set_nthreads(nthreads: Option<usize>) {
+    pool::set_nthreads(nthreads.unwrap_or_else(pool::default_nthreads));
+}
+
 #[pyfunction]
-pub fn set_nthreads(nthreads: usize) {
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(nthreads)
-        .build_global()
-        .unwrap();
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-#[pyclass]
-pub enum SingletonMode {
-    AutoPopulate,
-    Ignore,
-    AsIs,
-}
-
-pub fn populate_clusdf(g: &Graph, df: &mut DataFrame) -> anyhow::Result<()> {
-    let g = &g.data.graph;
-    let bitmaps: Vec<RoaringBitmap> = iter_roaring(df.column("nodes")?)
-        .map(|n| n.try_into().unwrap())
-        .collect_vec();
-    let edges_bitmaps = g
-        .nodes
-        .iter()
-        .map(|n| RoaringBitmap::from_sorted_iter(n.edges.iter().map(|it| *it as u32)).unwrap())
-        .collect_vec();
-    let data: Vec<_> = bitmaps
-        .into_par_iter()
-        .map(|nodes| {
-            let mut m = 0u64;
-            let mut c = 0u64;
-            let mut mcd = (g.m() + 1) as u64;
-            for u in nodes.iter() {
-                let adj = &edges_bitmaps[u as usize];
-                let ic = adj.intersection_len(&nodes);
-                m += ic;
-                c += adj.len() as u64 - ic;
-                mcd = mcd.min(ic);
-            }
-            if mcd == (g.m() + 1) as u64 {
-                mcd = 0;
-            }
-            m /= 2;
-            (nodes.len(), m, c, mcd)
-        })
-        .collect();
-    let mut n_s = Vec::with_capacity(data.len());
-    let mut m_s = Vec::with_capacity(data.len());
-    let mut c_s = Vec::with_capacity(data.len());
-    let mut mcd_s = Vec::with_capacity(data.len());
-    for (n, m, c, mcd) in data {
-        n_s.push(n);
-        m_s.push(m);
-        c_s.push(c);
-        mcd_s.push(mcd);
-    }
-    df.with_column(Series::new("n", n_s))?;
-    df.with_column(Series::new("m", m_s))?;
-    df.with_column(Series::new("c", c_s))?;
-    df.with_column(Series::new("mcd", mcd_s))?;
-    Ok(())
-}
-
-pub fn read_json<P: AsRef<Path>>(
-    g: &Graph,
-    filepath: P,
-    mode: SingletonMode,
-) -> anyhow::Result<DataFrame> {
-    let mut file = std::fs::File::open(filepath)?;
-    let mut df = JsonLineReader::new(&mut file).finish()?;
-    df.with_column(
-        df.column("nodes")?
-            .cast(&DataType::List(Box::new(DataType::UInt32)))?,
-    )?;
-    let mut nodes = node_list_to_bitmaps(g, df.column("nodes")?)?;
-    nodes.rename("nodes");
-    df.with_column(nodes)?;
-    df = postprocess_singleton_mode(g, df, mode)?;
-    populate_clusdf(g, &mut df)?;
-    Ok(df)
-}
-
-/// Postprocesses a data frame with the singleton mode specified
-pub fn postprocess_singleton_mode(
-    g: &Graph,
-    mut df: DataFrame,
-    mode: SingletonMode,
-) -> anyhow::Result<DataFrame> {
-    let lb = if mode == SingletonMode::Ignore {
-        2
-    } else {
-        1
-    };
-    let mask: Series = iter_roaring(df.column("nodes")?)
-            .map(|it| it.len() >= lb)
-            .collect();
-    df = df.filter(mask.bool()?)?;
-    if mode == SingletonMode::AutoPopulate {
-        let covered_nodes: RoaringBitmap = iter_roaring(df.column("nodes")?).collect_vec().union().try_into()?;
-        // create two columns, a column of labels and a column of nodes
-        let mut new_labels = vec![];
-        let mut new_nodes: Vec<EfficientSet> = vec![];
-        if covered_nodes.len() < g.n().into() {
-            for i in 0..g.n() {
-                if !covered_nodes.contains(i) {
-                    new_labels.push(AnyValue::Null);
-                    new_nodes.push(RoaringBitmap::from_iter([i]).into())
-                }
-            }
-        }
-        let new_labels =
-            Series::from_any_values_and_dtype("label", &new_labels, df.column("label")?.dtype())?;
-        let k = new_labels.len();
-        let mut extend_df = df!("label" => new_labels, "nodes" => new_nodes.to_series())?;
-        df.get_column_names_owned().iter().for_each(|col: &String| {
-            if col != "label" && col != "nodes" {
-                let mut null_filled = Vec::with_capacity(k);
-                for _i in 0..k {
-                    null_filled.push(AnyValue::Null);
-                }
-                let s = Series::from_any_values_and_dtype(
-                    col,
-                    &null_filled,
-                    df.column(col).unwrap().dtype(),
-                )
-                .unwrap();
-                extend_df.with_column(s).unwrap();
-            }
-        });
-        df.extend(&extend_df)?;
-    }
-    Ok(df)
+pub fn get_nthreads() -> usize {
+    pool::nthreads()
 }
 
-pub fn read_membership_series(
-    g: &Graph,
-    nodes: &Series,
-    cids: &Series,
-    mode: SingletonMode,
-) -> anyhow::Result<DataFrame> {
-    let df = df!("nid" => nodes.cast(&DataType::UInt32)?, "cid" => cids)?;
-    let mut df = df
-        .lazy()
-        .groupby(["cid"])
-        .agg([col("nid").list()])
-        .collect()?;
-    let lb = if mode == SingletonMode::Ignore {
-        2
-    } else {
-        1
-    };
-    let mask: Series = df
-            .column("nid")?
-            .list()?
-            .into_iter()
-            .map(|f| f.map_or(false, |e| e.len() >= lb))
-            .collect();
-    df = df.filter(mask.bool()?)?;
-    let mut nodes = node_list_to_bitmaps(g, df.column("nid")?)?;
-    nodes.rename("nodes");
-    let mut df = df!("label" => df.column("cid")?, "nodes" => nodes)?;
-    df = postprocess_singleton_mode(g, df, mode)?;
-    populate_clusdf(g, &mut df)?;
-    Ok(df)
-}
-
-pub fn read_membership_file(
-    g: &Graph,
-    filepath: &str,
-    sep: u8,
-    mode: SingletonMode,
-    force_string_labels: bool,
-) -> anyhow::Result<DataFrame> {
-    let df = CsvReader::from_path(filepath)?
-        .has_header(false)
-        .with_delimiter(sep)
-        .with_dtypes_slice(Some(&[DataType::UInt32, if force_string_labels {
-            DataType::Utf8
-        } else {
-            DataType::UInt32
-        }]))
-        .finish()?;
-    let nid = df.column("column_1")?;
-    let cid = df.column("column_2")?;
-    read_membership_series(g, nid, cid, mode)
+/// Runs computations started from the calling Python thread on `nthreads` threads until
+/// the matching `pop_nthreads` (see `threads`).
+#[pyfunction]
+pub fn push_nthreads(nthreads: usize) {
+    pool::push_scoped(nthreads);
+}
+
+#[pyfunction]
+pub fn pop_nthreads() {
+    pool::pop_scoped();
+}
+
+/// Sets the level of the `belinda` logger. Rust only forwards records at or above the
+/// level the logger had when belinda was imported or was last given here.
+#[pyfunction]
+pub fn set_log_level(py: Python, level: u32) -> PyResult<()> {
+    logging::set_level(py, level)
 }
 
 #[pyfunction(
     name = "read_membership",
     mode = "SingletonMode::AsIs",
     sep = "'\\t'",
-    force_string_labels = "false"
+    force_string_labels = "false",
+    progress = "None"
 )]
 pub fn py_read_membership_file(
+    py: Python,
     g: &Graph,
     filepath: &str,
     sep: char,
     mode: SingletonMode,
     force_string_labels: bool,
+    progress: Option<&PyAny>,
 ) -> PyResult<PyObject> {
-    let mut df = read_membership_file(g, filepath, sep as u8, mode, force_string_labels).unwrap();
-    let translated = translate_df(&mut df)?;
+    let progress = ProgressSpec::from_py(progress)?;
+    let mut df = pool::run(py, || {
+        read_membership_file(g, filepath, sep as u8, mode, force_string_labels, &progress)
+    })?
+    .into_df();
+    let translated = translate_df(py, &mut df)?;
     Ok(translated)
 }
 
-#[pyfunction(name = "read_membership_series", mode = "SingletonMode::AsIs")]
+#[pyfunction(
+    name = "read_membership_series",
+    mode = "SingletonMode::AsIs",
+    progress = "None"
+)]
 pub fn py_from_memberships(
+    py: Python,
     g: &Graph,
     nodes: &PyAny,
     cids: &PyAny,
     mode: SingletonMode,
+    progress: Option<&PyAny>,
 ) -> PyResult<PyObject> {
     let nodes = ffi::py_series_to_rust_series(nodes)?;
     let cids = ffi::py_series_to_rust_series(cids)?;
-    let mut df = read_membership_series(g, &nodes, &cids, mode).unwrap();
-    translate_df(&mut df)
-}
-
-#[pyfunction(name = "read_json", mode = "SingletonMode::AsIs")]
-pub fn py_read_json(g: &Graph, filepath: &str, mode: SingletonMode) -> PyResult<PyObject> {
-    let mut df = read_json(g, filepath, mode).unwrap();
-    translate_df(&mut df)
-}
-
-pub fn node_list_to_bitmaps(g: &Graph, list: &Series) -> anyhow::Result<Series> {
-    let g = &g.data.graph;
-    let as_list = list.list()?;
-    let sets: Vec<EfficientSet> = as_list
-        .par_iter()
-        .map(|e| {
-            e.map_or_else(
-                || RoaringBitmap::new().into(),
-                |series| {
-                    let mut seen_nonexistent = false;
-                    let mut bitmap = RoaringBitmap::new();
-                    series.u32().unwrap().into_iter().flatten().for_each(|x| {
-                        match g.retrieve(x as usize) {
-                            Some(internal_id) => {
-                                bitmap.insert(internal_id as u32);
-                            }
-                            None => {
-                                if seen_nonexistent {
-                                    panic!("Nonexistent node that is not singleton: {}", x);
-                                }
-                                seen_nonexistent = true;
-                            }
-                        }
-                    });
-                    bitmap.into()
-                },
-            )
-        })
-        .collect();
-    Ok(sets.to_series())
+    let progress = ProgressSpec::from_py(progress)?;
+    let mut df = pool::run(py, || {
+        read_membership_series(g, &nodes, &cids, mode, &progress)
+    })?
+    .into_df();
+    translate_df(py, &mut df)
 }
 
-#[pyclass]
-#[derive(Clone)]
-pub struct Graph {
-    data: Arc<EnrichedGraph>,
-    cc: OnceCell<CCLabels>,
+#[pyfunction(
+    name = "cluster_leiden",
+    objective = "\"cpm\"",
+    resolution = "1.0",
+    seed = "0",
+    iterations = "2",
+    initial = "None"
+)]
+pub fn py_cluster_leiden(
+    py: Python,
+    g: &Graph,
+    objective: &str,
+    resolution: f64,
+    seed: u64,
+    iterations: usize,
+    initial: Option<&PyAny>,
+) -> PyResult<PyObject> {
+    let config = LeidenConfig {
+        objective: objective.parse::<Objective>()?,
+        resolution,
+        seed,
+        iterations,
+    };
+    let initial = match initial {
+        Some(clus) => Some(ffi::py_series_to_rust_series(
+            clus.call_method1("get_column", ("nodes",))?,
+        )?),
+        None => None,
+    };
+    let mut df = pool::run(py, || {
+        let initial = match initial {
+            Some(nodes) => Some(stats::clusdf_to_membership(g, &nodes)?),
+            None => None,
+        };
+        g.leiden(&config, initial)
+    })?
+    .into_df();
+    translate_df(py, &mut df)
 }
 
-impl Graph {
-    pub fn get_cc_labels(&self) -> &CCLabels {
-        self.cc.get_or_init(|| alg::cc_labeling(&self.data.graph))
+/// The label propagation cluster frame, its convergence report repeated in the constant
+/// columns `lpa_iterations`, `lpa_converged` and `lpa_changed` so that it survives
+/// `with_columns` and `filter`.
+#[pyfunction(name = "cluster_label_propagation", seed = "0", max_iterations = "100")]
+pub fn py_cluster_label_propagation(
+    py: Python,
+    g: &Graph,
+    seed: u64,
+    max_iterations: usize,
+) -> PyResult<PyObject> {
+    let mut df = pool::run(py, || -> anyhow::Result<DataFrame> {
+        let (clus, report) = g.label_propagation(seed, max_iterations)?;
+        let mut df = clus.into_df();
+        let k = df.height();
+        df.with_column(Series::new(
+            "lpa_iterations",
+            vec![report.iterations as u32; k],
+        ))?;
+        df.with_column(Series::new("lpa_converged", vec![report.converged; k]))?;
+        df.with_column(Series::new("lpa_changed", vec![report.changed as u64; k]))?;
+        Ok(df)
+    })?;
+    translate_df(py, &mut df)
+}
+
+/// A binary file for the Python writers, compressed according to its extension like
+/// every Rust writer. Closing it writes the trailer of the compressed stream.
+#[pyclass(name = "CompressedWriter")]
+pub struct PyCompressedWriter {
+    writer: Option<compress::Writer>,
+}
+
+impl PyCompressedWriter {
+    fn writer(&mut self) -> PyResult<&mut compress::Writer> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("I/O operation on closed file"))
+    }
+}
+
+#[pymethods]
+impl PyCompressedWriter {
+    fn write(&mut self, py: Python, data: &[u8]) -> PyResult<usize> {
+        let writer = self.writer()?;
+        py.allow_threads(|| writer.write_all(data))?;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self, py: Python) -> PyResult<()> {
+        let writer = self.writer()?;
+        Ok(py.allow_threads(|| writer.flush())?)
+    }
+
+    fn close(&mut self, py: Python) -> PyResult<()> {
+        match self.writer.take() {
+            Some(writer) => Ok(py.allow_threads(|| writer.finish())?),
+            None => Ok(()),
+        }
     }
+
+    fn writable(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn closed(&self) -> bool {
+        self.writer.is_none()
+    }
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python,
+        _exc_type: &PyAny,
+        _exc_value: &PyAny,
+        _traceback: &PyAny,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+}
+
+/// Creates `filepath` for writing bytes, compressed as `.gz`, `.zst` or `.bz2` by extension.
+#[pyfunction(name = "open_compressed")]
+pub fn py_open_compressed(filepath: &str) -> PyResult<PyCompressedWriter> {
+    Ok(PyCompressedWriter {
+        writer: Some(compress::create(filepath)?),
+    })
+}
+
+#[pyfunction(name = "read_json", mode = "SingletonMode::AsIs", progress = "None")]
+pub fn py_read_json(
+    py: Python,
+    g: &Graph,
+    filepath: &str,
+    mode: SingletonMode,
+    progress: Option<&PyAny>,
+) -> PyResult<PyObject> {
+    let progress = ProgressSpec::from_py(progress)?;
+    let mut df = pool::run(py, || read_json(g, filepath, mode, &progress))?.into_df();
+    translate_df(py, &mut df)
 }
 
 #[pymethods]
 impl Graph {
     #[new]
-    fn new(filepath: &str) -> anyhow::Result<Self> {
-        let raw_data =
-            EnrichedGraph::from_graph(aocluster::base::Graph::parse_from_file(filepath)?);
-        Ok(Graph {
-            data: Arc::new(raw_data),
-            cc: OnceCell::new(),
-        })
+    #[args(
+        progress = "None",
+        sep = "None",
+        comment = "Some(\"#\".to_string())",
+        skip_header = "false",
+        columns = "(0, 1)",
+        self_loops = "\"drop\"",
+        duplicates = "\"drop\"",
+        symmetrize = "\"union\"",
+        directed = "false"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        py: Python,
+        filepath: &str,
+        progress: Option<&PyAny>,
+        sep: Option<char>,
+        comment: Option<String>,
+        skip_header: bool,
+        columns: (usize, usize),
+        self_loops: &str,
+        duplicates: &str,
+        symmetrize: &str,
+        directed: bool,
+    ) -> PyResult<Self> {
+        let progress = ProgressSpec::from_py(progress)?;
+        let options = GraphReadOptions {
+            delimiter: sep,
+            comment,
+            skip_header,
+            columns,
+            self_loops: self_loops.parse::<Policy>()?,
+            duplicates: duplicates.parse::<Policy>()?,
+            symmetrize: symmetrize.parse::<Symmetrize>()?,
+            directed,
+        };
+        Ok(pool::run(py, || -> anyhow::Result<Graph> {
+            let progress = progress.start(None, "reading graph");
+            let g = Graph::open_with(filepath, &options)?;
+            progress.finish();
+            Ok(g)
+        })?)
     }
 
-    fn write_edgelist(&self, filepath: &str) -> anyhow::Result<()> {
-        let g = &self.data.graph;
-        let mut w = BufWriter::new(File::create(filepath)?);
-        for u in &g.nodes {
-            for v in &u.edges {
-                if u.id < *v {
-                    let lhs = g.name_set.rev[u.id as usize];
-                    let rhs = g.name_set.rev[*v as usize];
-                    writeln!(w, "{}\t{}", lhs, rhs)?;
-                }
-            }
-        }
-        Ok(())
-    }
-
-    #[args(verbose = false)]
-    fn nodes(&self, clus: Option<&PyAny>, verbose: bool) -> PyResult<PyObject> {
-        let g = &self.data.graph;
-        let nodes = (0..self.n())
-            .map(|it| g.name_set.rev[it as usize] as u32)
-            .collect_vec();
-        let degrees = (0..self.n())
-            .map(|it| g.nodes[it as usize].degree() as u32)
-            .collect_vec();
-        let mut df = df!(
-            "node" => nodes,
-            "degree" => degrees,
-        )
-        .unwrap();
-        if verbose {
-            let adj = (0..self.n())
-                .map(|it| {
-                    g.nodes[it as usize]
-                        .edges
-                        .iter()
-                        .map(|it| g.name_set.rev[*it] as u32)
-                        .collect::<Series>()
-                })
-                .collect_vec();
-            df.with_column(Series::new("adj", adj)).unwrap();
-        }
-        if let Some(clus) = clus {
-            let label =
-                ffi::py_series_to_rust_series(clus.call_method1("get_column", ("label",))?)?;
-            let label_t = label.dtype();
-            let mut labels_u32: Vec<Vec<Option<u32>>> = vec![vec![]; self.n() as usize];
-            let mut labels_str: Vec<Vec<String>> = vec![vec![]; self.n() as usize];
-            let nodes =
-                ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
-            if label_t != &DataType::Utf8 {
-                for (ns, label) in
-                    iter_roaring(&nodes).zip(label.cast(&DataType::UInt32).unwrap().u32().unwrap())
-                {
-                    let ns: RoaringBitmap = ns.try_into().unwrap();
-                    for node in ns.into_iter() {
-                        labels_u32[node as usize].push(label);
-                    }
-                }
-            } else {
-                for (ns, label) in iter_roaring(&nodes).zip(label.utf8().unwrap()) {
-                    let ns: RoaringBitmap = ns.try_into().unwrap();
-                    for node in ns.into_iter() {
-                        labels_str[node as usize].push(label.unwrap_or_default().to_string());
-                    }
-                }
+    /// Counts of the lines skipped when reading the graph, by reason.
+    #[getter(read_report)]
+    fn py_read_report(&self, py: Python) -> PyResult<PyObject> {
+        let report = match self.read_report() {
+            Some(report) => report,
+            None => return Ok(py.None()),
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("lines", report.lines)?;
+        dict.set_item("skipped", report.skipped())?;
+        dict.set_item("blank", report.blank)?;
+        dict.set_item("comments", report.comments)?;
+        dict.set_item("header", report.header)?;
+        dict.set_item("self_loops", report.self_loops)?;
+        dict.set_item("duplicates", report.duplicates)?;
+        dict.set_item("unreciprocated", report.unreciprocated)?;
+        Ok(dict.into())
+    }
+
+    /// Accepts either a single entry of a `nodes` column or a series of them (e.g. from `set.union()`).
+    fn subgraph(&self, py: Python, nodes: &PyAny) -> PyResult<Graph> {
+        let set = match nodes.downcast::<PyBytes>() {
+            Ok(bytes) => deserialize_set(bytes.as_bytes())?,
+            Err(_) => {
+                let series = ffi::py_series_to_rust_series(nodes)?;
+                pool::run(py, || iter_roaring(&series).collect_vec().union())
             }
-            let labels_u32 = labels_u32
-                .into_iter()
-                .map(|it| it.into_iter().collect::<Series>())
-                .collect_vec();
-            let labels_str = labels_str
-                .into_iter()
-                .map(|it| it.into_iter().collect::<Series>())
-                .collect_vec();
-            if label_t != &DataType::Utf8 {
-                df.with_column(Series::new("labels", labels_u32)).unwrap();
+        };
+        let set: RoaringBitmap = set.try_into().map_err(anyhow::Error::from)?;
+        Ok(pool::run(py, || self.induced_subgraph(&set)))
+    }
+
+    #[pyo3(name = "write_edgelist")]
+    fn py_write_edgelist(&self, py: Python, filepath: &str) -> anyhow::Result<()> {
+        pool::run(py, || self.write_edgelist(filepath))
+    }
+
+    #[args(
+        verbose = false,
+        pagerank = false,
+        damping = "0.85",
+        eigenvector = false,
+        k_hop = "None",
+        tolerance = "1e-6",
+        max_iterations = "100",
+        betweenness_samples = "None",
+        epsilon = "None",
+        delta = "0.1",
+        seed = "0"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn nodes(
+        &self,
+        py: Python,
+        clus: Option<&PyAny>,
+        verbose: bool,
+        pagerank: bool,
+        damping: f64,
+        eigenvector: bool,
+        k_hop: Option<u32>,
+        tolerance: f64,
+        max_iterations: usize,
+        betweenness_samples: Option<usize>,
+        epsilon: Option<f64>,
+        delta: f64,
+        seed: u64,
+    ) -> PyResult<PyObject> {
+        let centrality = CentralityColumns {
+            pagerank: pagerank.then_some(damping),
+            eigenvector,
+            k_hop,
+            betweenness: betweenness_sampling(betweenness_samples, epsilon, delta)?,
+            seed,
+            tolerance,
+            max_iterations,
+        };
+        let clustering = match clus {
+            Some(clus) => Some((
+                ffi::py_series_to_rust_series(clus.call_method1("get_column", ("label",))?)?,
+                ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?,
+            )),
+            None => None,
+        };
+        let mut df = pool::run(py, || {
+            self.nodes_df(
+                clustering.as_ref().map(|(label, nodes)| (label, nodes)),
+                verbose,
+                &centrality,
+            )
+        })?;
+        translate_df(py, &mut df)
+    }
+
+    fn edges(&self, py: Python, clus: Option<&PyAny>) -> PyResult<PyObject> {
+        let clustering = match clus {
+            Some(clus) => Some((
+                ffi::py_series_to_rust_series(clus.call_method1("get_column", ("label",))?)?,
+                ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?,
+            )),
+            None => None,
+        };
+        let mut df = pool::run(py, || {
+            self.edges_df(clustering.as_ref().map(|(label, nodes)| (label, nodes)))
+        })?;
+        translate_df(py, &mut df)
+    }
+
+    #[args(progress = "None")]
+    fn covered_edges(&self, py: Python, n: &PyAny, progress: Option<&PyAny>) -> PyResult<PyObject> {
+        let series = ffi::py_series_to_rust_series(n)?;
+        let progress = ProgressSpec::from_py(progress)?;
+        let out = pool::run(py, || stats::covered_edges(self, &series, &progress));
+        ffi::rust_series_to_py_series(py, &out)
+    }
+
+    /// Edge ids of the `(src, dst)` pairs given in original ids, null where there is no such edge.
+    /// With `as_set`, the found ids are instead collected into a single edge set.
+    #[args(as_set = false)]
+    fn edge_ids(&self, py: Python, src: &PyAny, dst: &PyAny, as_set: bool) -> PyResult<PyObject> {
+        let src = ffi::py_series_to_rust_series(src)?;
+        let dst = ffi::py_series_to_rust_series(dst)?;
+        let out = pool::run(py, || -> anyhow::Result<Series> {
+            let ids = self.edge_ids_of(&src, &dst)?;
+            if as_set {
+                let set = RoaringTreemap::from_iter(ids.into_iter().flatten());
+                Ok(build_series_from_sets(vec![set.into()]))
             } else {
-                df.with_column(Series::new("labels", labels_str)).unwrap();
+                Ok(Series::new("edge_id", ids))
             }
-        }
-        translate_df(&mut df)
+        })?;
+        ffi::rust_series_to_py_series(py, &out)
     }
 
-    fn covered_edges(&self, n: &PyAny) -> PyResult<PyObject> {
+    #[args(progress = "None")]
+    fn covered_edges_count(
+        &self,
+        py: Python,
+        n: &PyAny,
+        progress: Option<&PyAny>,
+    ) -> PyResult<u64> {
         let series = ffi::py_series_to_rust_series(n)?;
-        let g = &self.data;
-        let nodesets = iter_roaring(&series)
-            .map(|it| it.try_into().unwrap())
-            .map(|it| edgeset(g, &it))
-            .map(EfficientSet::BigSet)
-            .collect::<Vec<_>>();
-        ffi::rust_series_to_py_series(&build_series_from_sets(nodesets))
+        let progress = ProgressSpec::from_py(progress)?;
+        Ok(pool::run(py, || {
+            stats::covered_edges_count(self, &series, &progress)
+        }))
     }
 
-    fn covered_edges_count(&self, n: &PyAny) -> PyResult<u64> {
-        let series = ffi::py_series_to_rust_series(n)?;
-        let g = &self.data;
-        let edgesets = iter_roaring(&series)
-            .map(|it| it.try_into().unwrap())
-            .par_bridge()
-            .map(|it| edgeset(g, &it))
-            .collect::<Vec<_>>();
-        Ok(edgesets.union().len() as u64)
+    #[getter(n)]
+    fn py_n(&self) -> u32 {
+        self.n()
     }
 
-    #[getter]
-    fn n(&self) -> u32 {
-        self.data.graph.n() as u32
+    #[getter(m)]
+    fn py_m(&self) -> u64 {
+        self.m()
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("Graph(n={}, m={})", self.n(), self.m()))
+    }
+
+    #[getter(directed)]
+    fn py_is_directed(&self) -> bool {
+        self.is_directed()
     }
 
+    /// The number of arcs of a directed graph.
     #[getter]
-    fn m(&self) -> u64 {
-        self.data.graph.m() as u64
+    fn m_arcs(&self) -> anyhow::Result<u64> {
+        Ok(self.directed()?.m())
     }
 
-    fn __str__(&self) -> PyResult<String> {
-        Ok(format!(
-            "Graph(n={}, m={})",
-            self.data.graph.n(),
-            self.data.graph.m()
-        ))
+    fn num_strong_components(&self, py: Python) -> anyhow::Result<u32> {
+        pool::run(py, || Ok(self.get_strong_cc_labels()?.sizes.len() as u32))
+    }
+
+    fn largest_strong_component(&self, py: Python) -> anyhow::Result<u32> {
+        pool::run(py, || {
+            Ok(self
+                .get_strong_cc_labels()?
+                .sizes
+                .iter()
+                .max()
+                .copied()
+                .unwrap_or(0))
+        })
     }
 
-    fn num_components(&self) -> u32 {
-        self.get_cc_labels().num_nodes.len() as u32
+    fn num_weak_components(&self, py: Python) -> anyhow::Result<u32> {
+        pool::run(py, || Ok(self.get_weak_cc_labels()?.sizes.len() as u32))
     }
 
-    fn largest_component(&self) -> u32 {
-        self.get_cc_labels()
-            .num_nodes
-            .iter()
-            .max()
-            .copied()
-            .unwrap() as u32
+    /// The nodes within `max_depth` hops of `source`, with their distances.
+    #[pyo3(name = "bfs")]
+    #[args(max_depth = "None")]
+    fn py_bfs(&self, py: Python, source: usize, max_depth: Option<u32>) -> PyResult<PyObject> {
+        let mut df = pool::run(py, || self.bfs(source, max_depth))?;
+        translate_df(py, &mut df)
     }
-}
 
-pub fn rust_label_cc(g: &Graph, series: &Series) -> anyhow::Result<Series> {
-    let labels = &g.get_cc_labels().labels;
-    let g = &g.data.graph;
-    let mut ans = vec![];
-    for u in series.u32().into_iter() {
-        for v in u {
-            ans.push(v.map(|v| labels[g.retrieve(v as usize).unwrap() as usize]));
-        }
+    /// The nodes of a shortest path from `a` to `b`, `None` if there is none.
+    #[pyo3(name = "shortest_path")]
+    fn py_shortest_path(&self, py: Python, a: usize, b: usize) -> PyResult<Option<Vec<usize>>> {
+        Ok(pool::run(py, || self.shortest_path(a, b))?)
     }
-    Ok(Series::new("cc", ans))
-}
 
-pub fn rust_label_cc_size(g: &Graph, series: &Series) -> anyhow::Result<Series> {
-    let num_nodes = &g.get_cc_labels().num_nodes;
-    let mut ans = vec![];
-    for u in series.u32().into_iter() {
-        for v in u {
-            ans.push(v.map(|v| num_nodes[v as usize]));
-        }
+    /// The nodes within `radius` hops of a node or a list of nodes, as a node set.
+    #[pyo3(name = "ego")]
+    #[args(radius = "1")]
+    fn py_ego(&self, py: Python, nodes: &PyAny, radius: u32) -> PyResult<PyObject> {
+        let nodes: Vec<usize> = match nodes.extract::<usize>() {
+            Ok(node) => vec![node],
+            Err(_) => nodes.extract()?,
+        };
+        let set = pool::run(py, || self.ego(&nodes, radius))?;
+        let mut bytes = vec![];
+        serialize_set(&set, &mut bytes)?;
+        Ok(PyBytes::new(py, &bytes).into())
     }
-    Ok(Series::new("cc_size", ans))
-}
 
-pub fn rust_nodeset_to_list(g: &Graph, series: &Series) -> anyhow::Result<Series> {
-    let mut ans = vec![];
-    let g = &g.data.graph;
-    for bm in iter_roaring(series) {
-        let bm: RoaringBitmap = bm.try_into()?;
-        let s = bm
-            .iter()
-            .map(|it| g.name_set.rev[it as usize] as u32)
-            .collect::<Series>();
-        ans.push(s);
+    /// The connected components as a cluster data frame, with diameter bounds.
+    #[pyo3(name = "components")]
+    fn py_components(&self, py: Python) -> PyResult<PyObject> {
+        let frame = pool::run(py, || self.components())?;
+        translate_df(py, &mut frame.into_df())
+    }
+
+    fn num_biconnected_components(&self, py: Python) -> u32 {
+        pool::run(py, || self.get_biconnectivity().num_components)
+    }
+
+    #[pyo3(name = "articulation_points")]
+    fn py_articulation_points(&self, py: Python) -> PyResult<PyObject> {
+        let points = pool::run(py, || self.articulation_points());
+        ffi::rust_series_to_py_series(py, &Series::new("node", points))
     }
-    Ok(Series::new("nodes_list", ans))
-}
 
-pub fn rust_popcnt(series: &Series) -> Series {
-    iter_roaring(series)
-        .map(|bitmap| bitmap.len() as u32)
-        .collect()
+    fn bridges(&self, py: Python) -> PyResult<PyObject> {
+        let mut df = pool::run(py, || self.biconnected_df(true))?;
+        translate_df(py, &mut df)
+    }
+
+    fn biconnected_components(&self, py: Python) -> PyResult<PyObject> {
+        let mut df = pool::run(py, || self.biconnected_df(false))?;
+        translate_df(py, &mut df)
+    }
+
+    #[pyo3(name = "num_components")]
+    fn py_num_components(&self, py: Python) -> u32 {
+        pool::run(py, || self.num_components())
+    }
+
+    #[pyo3(name = "largest_component")]
+    fn py_largest_component(&self, py: Python) -> u32 {
+        pool::run(py, || self.largest_component())
+    }
 }
 
-pub fn rust_bitmap_union(series: &Series) -> Series {
-    let s = iter_roaring(series).collect::<Vec<EfficientSet>>();
-    build_series_from_sets(vec![s.union()])
+#[pyfunction(name = "quotient_edges")]
+pub fn py_quotient_edges(py: Python, g: &Graph, clus: &PyAny) -> PyResult<PyObject> {
+    let labels = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("label",))?)?;
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || stats::quotient_edges(g, &labels, &nodes))?;
+    translate_df(py, &mut df)
 }
 
-fn edgeset(g: &EnrichedGraph, bm: &RoaringBitmap) -> RoaringTreemap {
-    let graph = &g.graph;
-    let acc = &g.acc_num_edges;
-    let tm = RoaringTreemap::from_sorted_iter(bm.iter().flat_map(|u| {
-        let edges = &graph.nodes[u as usize].edges;
-        let shift = acc[u as usize];
-        edges
-            .iter()
-            .filter(move |e| u < **e as u32)
-            .enumerate()
-            .filter_map(move |(offset, &v)| {
-                if bm.contains(v as u32) {
-                    Some(shift + offset as u64)
-                } else {
-                    None
-                }
-            })
-    }))
-    .unwrap();
-    tm
+#[pyfunction(name = "quotient_graph")]
+pub fn py_quotient_graph(py: Python, g: &Graph, clus: &PyAny) -> PyResult<(Graph, PyObject)> {
+    let labels = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("label",))?)?;
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let (quotient, mut edges) = pool::run(py, || stats::quotient_graph(g, &labels, &nodes))?;
+    Ok((quotient, translate_df(py, &mut edges)?))
 }
 
 #[pyfunction(name = "popcnt")]
-pub fn py_popcnt(series: &PyAny) -> PyResult<PyObject> {
+pub fn py_popcnt(py: Python, series: &PyAny) -> PyResult<PyObject> {
     let series = ffi::py_series_to_rust_series(series)?;
-    let out = rust_popcnt(&series);
-    ffi::rust_series_to_py_series(&out)
+    let out = pool::run(py, || stats::popcnt(&series));
+    ffi::rust_series_to_py_series(py, &out)
 }
 
 #[pyfunction(name = "union")]
-pub fn py_bitmap_union(series: &PyAny) -> PyResult<PyObject> {
+pub fn py_bitmap_union(py: Python, series: &PyAny) -> PyResult<PyObject> {
     let series = ffi::py_series_to_rust_series(series)?;
-    let out = rust_bitmap_union(&series);
-    ffi::rust_series_to_py_series(&out)
+    let out = pool::run(py, || stats::bitmap_union(&series));
+    ffi::rust_series_to_py_series(py, &out)
 }
 
 #[pyfunction(name = "cc_labels")]
-pub fn py_label_cc(g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+pub fn py_label_cc(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
     let series = ffi::py_series_to_rust_series(series)?;
-    let out = rust_label_cc(g, &series).unwrap();
-    ffi::rust_series_to_py_series(&out)
+    let out = pool::run(py, || stats::label_cc(g, &series))?;
+    ffi::rust_series_to_py_series(py, &out)
 }
 
 #[pyfunction(name = "cc_size")]
-pub fn py_label_cc_size(g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+pub fn py_label_cc_size(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+    let series = ffi::py_series_to_rust_series(series)?;
+    let out = pool::run(py, || stats::label_cc_size(g, &series))?;
+    ffi::rust_series_to_py_series(py, &out)
+}
+
+#[pyfunction(name = "edgeset_to_list")]
+pub fn py_edgeset_to_list(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
     let series = ffi::py_series_to_rust_series(series)?;
-    let out = rust_label_cc_size(g, &series).unwrap();
-    ffi::rust_series_to_py_series(&out)
+    let out = pool::run(py, || stats::edgeset_to_list(g, &series))?;
+    ffi::rust_series_to_py_series(py, &out)
 }
 
 #[pyfunction(name = "nodeset_to_list")]
-pub fn py_nodeset_to_list(g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+pub fn py_nodeset_to_list(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
     let series = ffi::py_series_to_rust_series(series)?;
-    let out = rust_nodeset_to_list(g, &series).unwrap();
-    ffi::rust_series_to_py_series(&out)
+    let out = pool::run(py, || stats::nodeset_to_list(g, &series))?;
+    ffi::rust_series_to_py_series(py, &out)
 }
 
+#[pyfunction(name = "nodeset_expand")]
+pub fn py_nodeset_expand(py: Python, g: &Graph, series: &PyAny, hops: u32) -> PyResult<PyObject> {
+    let series = ffi::py_series_to_rust_series(series)?;
+    let out = pool::run(py, || stats::nodeset_expand(g, &series, hops))?;
+    ffi::rust_series_to_py_series(py, &out)
+}
+
+#[pyfunction(name = "nodeset_boundary")]
+pub fn py_nodeset_boundary(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+    let series = ffi::py_series_to_rust_series(series)?;
+    let out = pool::run(py, || stats::nodeset_boundary(g, &series))?;
+    ffi::rust_series_to_py_series(py, &out)
+}
+
+#[pyfunction(name = "boundary_size")]
+pub fn py_boundary_size(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+    let series = ffi::py_series_to_rust_series(series)?;
+    let out = pool::run(py, || stats::boundary_size(g, &series))?;
+    ffi::rust_series_to_py_series(py, &out)
+}
+
+#[pyfunction(name = "wcc_labels")]
+pub fn py_label_wcc(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+    let series = ffi::py_series_to_rust_series(series)?;
+    let out = pool::run(py, || stats::label_directed_cc(g, &series, false))?;
+    ffi::rust_series_to_py_series(py, &out)
+}
+
+#[pyfunction(name = "scc_labels")]
+pub fn py_label_scc(py: Python, g: &Graph, series: &PyAny) -> PyResult<PyObject> {
+    let series = ffi::py_series_to_rust_series(series)?;
+    let out = pool::run(py, || stats::label_directed_cc(g, &series, true))?;
+    ffi::rust_series_to_py_series(py, &out)
+}
+
+/// Appends the `core_node`, `max_pagerank` and `pagerank_mass` columns to a cluster data frame.
+#[pyfunction(
+    name = "centrality_statistics",
+    damping = "0.85",
+    tolerance = "1e-6",
+    max_iterations = "100"
+)]
+pub fn py_centrality_statistics(
+    py: Python,
+    g: &Graph,
+    clus: &PyAny,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> PyResult<PyObject> {
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || {
+        stats::centrality_statistics(g, &nodes, damping, tolerance, max_iterations)
+    })?;
+    let columns = translate_df(py, &mut df)?;
+    Ok(clus.call_method1("hstack", (columns,))?.into())
+}
+
+/// Betweenness from `samples` sources, or within `epsilon` with probability `1 - delta`.
+fn betweenness_sampling(
+    samples: Option<usize>,
+    epsilon: Option<f64>,
+    delta: f64,
+) -> anyhow::Result<Option<BetweennessSampling>> {
+    Ok(match (samples, epsilon) {
+        (Some(_), Some(_)) => anyhow::bail!("expected either samples or epsilon, not both"),
+        (Some(k), None) => Some(BetweennessSampling::Sources(k)),
+        (None, Some(epsilon)) => Some(BetweennessSampling::ErrorBound { epsilon, delta }),
+        (None, None) => None,
+    })
+}
+
+/// Appends the `boundary_betweenness` column to a cluster data frame.
+#[pyfunction(
+    name = "betweenness_statistics",
+    samples = "None",
+    epsilon = "None",
+    delta = "0.1",
+    seed = "0"
+)]
+pub fn py_betweenness_statistics(
+    py: Python,
+    g: &Graph,
+    clus: &PyAny,
+    samples: Option<usize>,
+    epsilon: Option<f64>,
+    delta: f64,
+    seed: u64,
+) -> PyResult<PyObject> {
+    let sampling = betweenness_sampling(samples, epsilon, delta)?
+        .ok_or_else(|| anyhow::anyhow!("expected either samples or epsilon"))?;
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || {
+        stats::betweenness_statistics(g, &nodes, sampling, seed)
+    })?;
+    let columns = translate_df(py, &mut df)?;
+    Ok(clus.call_method1("hstack", (columns,))?.into())
+}
+
+/// Appends the `n_components` and `component_coverage` columns to a cluster data frame.
+#[pyfunction(name = "cluster_component_coverage")]
+pub fn py_cluster_component_coverage(py: Python, g: &Graph, clus: &PyAny) -> PyResult<PyObject> {
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || stats::cluster_component_coverage(g, &nodes))?;
+    let columns = translate_df(py, &mut df)?;
+    Ok(clus.call_method1("hstack", (columns,))?.into())
+}
+
+/// How many clusters touch each connected component, and how many of its nodes none covers.
+#[pyfunction(name = "component_coverage")]
+pub fn py_component_coverage(py: Python, g: &Graph, clus: &PyAny) -> PyResult<PyObject> {
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || stats::component_cluster_coverage(g, &nodes))?;
+    translate_df(py, &mut df)
+}
+
+/// Appends Yang & Leskovec's scoring functions (see `stats::quality_statistics`) to a
+/// cluster data frame.
+#[pyfunction(name = "quality_statistics", progress = "None")]
+pub fn py_quality_statistics(
+    py: Python,
+    g: &Graph,
+    clus: &PyAny,
+    progress: Option<&PyAny>,
+) -> PyResult<PyObject> {
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let progress = ProgressSpec::from_py(progress)?;
+    let mut df = pool::run(py, || stats::quality_statistics(g, &nodes, &progress))?;
+    let columns = translate_df(py, &mut df)?;
+    Ok(clus.call_method1("hstack", (columns,))?.into())
+}
+
+/// Z-scores and p-values of the internal edge counts `m` of clusters of `n` nodes and
+/// volume `vol` under a null model, simulated if `samples` is given.
+#[pyfunction(
+    name = "null_significance",
+    null = "\"configuration\"",
+    samples = "None",
+    seed = "0"
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_null_significance(
+    py: Python,
+    g: &Graph,
+    n: &PyAny,
+    m: &PyAny,
+    vol: &PyAny,
+    null: &str,
+    samples: Option<usize>,
+    seed: u64,
+) -> PyResult<PyObject> {
+    let null: NullModel = null.parse()?;
+    let (n, m, vol) = (
+        ffi::py_series_to_rust_series(n)?,
+        ffi::py_series_to_rust_series(m)?,
+        ffi::py_series_to_rust_series(vol)?,
+    );
+    let mut df = pool::run(py, || {
+        stats::null_significance(g, &n, &m, &vol, null, samples, seed)
+    })?;
+    translate_df(py, &mut df)
+}
+
+/// Appends the `n_articulation_points` and `n_bridges` columns to a cluster data frame.
+#[pyfunction(name = "cut_statistics")]
+pub fn py_cut_statistics(py: Python, g: &Graph, clus: &PyAny) -> PyResult<PyObject> {
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || stats::cut_statistics(g, &nodes))?;
+    let columns = translate_df(py, &mut df)?;
+    Ok(clus.call_method1("hstack", (columns,))?.into())
+}
+
+/// Appends the directed statistics columns to a cluster data frame of a directed graph.
+#[pyfunction(name = "directed_statistics", resolution = "1.0")]
+pub fn py_directed_statistics(
+    py: Python,
+    g: &Graph,
+    clus: &PyAny,
+    resolution: f64,
+) -> PyResult<PyObject> {
+    let nodes = ffi::py_series_to_rust_series(clus.call_method1("get_column", ("nodes",))?)?;
+    let mut df = pool::run(py, || stats::directed_cluster_stats(g, &nodes, resolution))?;
+    let columns = translate_df(py, &mut df)?;
+    Ok(clus.call_method1("hstack", (columns,))?.into())
+}
+
+/// An Erdős–Rényi graph G(n, p) on nodes `0..n`.
+#[pyfunction(name = "erdos_renyi", seed = "0")]
+pub fn py_erdos_renyi(py: Python, n: usize, p: f64, seed: u64) -> PyResult<Graph> {
+    Ok(pool::run(py, || generators::erdos_renyi(n, p, seed))?)
+}
+
+/// An erased configuration model graph with (at most) the given degrees.
+#[pyfunction(name = "configuration_model", seed = "0")]
+pub fn py_configuration_model(py: Python, degrees: Vec<usize>, seed: u64) -> PyResult<Graph> {
+    Ok(pool::run(py, || {
+        generators::configuration_model(&degrees, seed)
+    })?)
+}
+
+/// A stochastic block model graph and its blocks as a cluster data frame.
+#[pyfunction(name = "stochastic_block_model", seed = "0")]
+pub fn py_stochastic_block_model(
+    py: Python,
+    sizes: Vec<usize>,
+    probabilities: Vec<Vec<f64>>,
+    seed: u64,
+) -> PyResult<(Graph, PyObject)> {
+    let (g, clusters) = pool::run(py, || {
+        generators::stochastic_block_model(&sizes, &probabilities, seed)
+    })?;
+    Ok((g, translate_df(py, &mut clusters.into_df())?))
+}
+
+/// An LFR benchmark graph and its planted communities as a cluster data frame.
+#[pyfunction(
+    name = "lfr",
+    n = "1000",
+    average_degree = "10.0",
+    max_degree = "50",
+    mixing = "0.1",
+    degree_exponent = "2.5",
+    community_exponent = "1.5",
+    min_community = "None",
+    max_community = "None",
+    seed = "0"
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn py_lfr(
+    py: Python,
+    n: usize,
+    average_degree: f64,
+    max_degree: usize,
+    mixing: f64,
+    degree_exponent: f64,
+    community_exponent: f64,
+    min_community: Option<usize>,
+    max_community: Option<usize>,
+    seed: u64,
+) -> PyResult<(Graph, PyObject)> {
+    let config = LfrConfig {
+        n,
+        average_degree,
+        max_degree,
+        mixing,
+        degree_exponent,
+        community_exponent,
+        min_community,
+        max_community,
+        seed,
+    };
+    let (g, clusters) = pool::run(py, || generators::lfr(&config))?;
+    Ok((g, translate_df(py, &mut clusters.into_df())?))
+}
@@ -58,27 +58,33 @@ pub struct PySeries {
     data: PyObject,
 }
 
-pub fn series_to_arrow(series: &mut Series) -> PyResult<PySeries> {
-    let series = series.rechunk();
-    let gil = Python::acquire_gil();
-    let py = gil.python();
+/// Exports a single-chunk arrow array to pyarrow. Only this step needs the GIL.
+pub fn series_to_arrow(py: Python, name: String, array: ArrayRef) -> PyResult<PySeries> {
     let pyarrow = py.import("pyarrow")?;
-    let py_array = to_py_array(py, pyarrow, series.chunks()[0].clone())?;
+    let py_array = to_py_array(py, pyarrow, array)?;
     let py_series = PySeries {
-        name: series.name().to_string(),
+        name,
         data: py_array,
     };
     Ok(py_series)
 }
 
-pub fn translate_df(df: &mut DataFrame) -> PyResult<PyObject> {
-    let columns = df.get_columns_mut();
-    let py_series = columns
-        .iter_mut()
-        .map(series_to_arrow)
+pub fn translate_df(py: Python, df: &mut DataFrame) -> PyResult<PyObject> {
+    let columns = df.get_columns();
+    // rechunking can copy whole columns, so do it without holding the GIL
+    let arrays = py.allow_threads(|| {
+        columns
+            .iter()
+            .map(|series| {
+                let series = series.rechunk();
+                (series.name().to_string(), series.chunks()[0].clone())
+            })
+            .collect::<Vec<_>>()
+    });
+    let py_series = arrays
+        .into_iter()
+        .map(|(name, array)| series_to_arrow(py, name, array))
         .collect::<PyResult<Vec<PySeries>>>()?;
-    let gil = Python::acquire_gil();
-    let py = gil.python();
     let pypolars = py.import("polars")?;
     let py_series_obj: Vec<_> = py_series
         .into_iter()
@@ -109,22 +115,18 @@ pub fn py_series_to_rust_series(series: &PyAny) -> PyResult<Series> {
     Series::try_from((name.as_str(), array)).map_err(|e| PyValueError::new_err(format!("{}", e)))
 }
 
-pub fn rust_series_to_py_series(series: &Series) -> PyResult<PyObject> {
+pub fn rust_series_to_py_series(py: Python, series: &Series) -> PyResult<PyObject> {
     // ensure we have a single chunk
-    let series = series.rechunk();
-    let array = series.to_arrow(0);
-
-    // acquire the gil
-    Python::with_gil(|py| {
-        // import pyarrow
-        let pyarrow = py.import("pyarrow")?;
-
-        // pyarrow array
-        let pyarrow_array = to_py_array(py, pyarrow, array)?;
-
-        // import polars
-        let polars = py.import("polars")?;
-        let out = polars.call_method1("from_arrow", (pyarrow_array,))?;
-        Ok(out.to_object(py))
-    })
+    let array = py.allow_threads(|| series.rechunk().to_arrow(0));
+
+    // import pyarrow
+    let pyarrow = py.import("pyarrow")?;
+
+    // pyarrow array
+    let pyarrow_array = to_py_array(py, pyarrow, array)?;
+
+    // import polars
+    let polars = py.import("polars")?;
+    let out = polars.call_method1("from_arrow", (pyarrow_array,))?;
+    Ok(out.to_object(py))
 }
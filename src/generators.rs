@@ -0,0 +1,342 @@
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{builder, cluster::ClusterFrame, graph::Graph, stats::membership_to_clusdf};
+
+/// G(n, p): every pair of the `n` nodes is an edge with probability `p`.
+pub fn erdos_renyi(n: usize, p: f64, seed: u64) -> anyhow::Result<Graph> {
+    anyhow::ensure!((0.0..=1.0).contains(&p), "p must be in [0, 1], got {}", p);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let total = (n * n.saturating_sub(1) / 2) as u64;
+    let edges = bernoulli_indices(&mut rng, total, p)
+        .into_iter()
+        .map(pair_of)
+        .collect::<Vec<_>>();
+    Ok(assemble(n, edges))
+}
+
+/// The erased configuration model: stubs matched uniformly at random, then self-loops
+/// and repeated edges dropped, so high degrees come out slightly lower than asked.
+pub fn configuration_model(degrees: &[usize], seed: u64) -> anyhow::Result<Graph> {
+    anyhow::ensure!(
+        degrees.iter().sum::<usize>() % 2 == 0,
+        "the degrees must sum to an even number"
+    );
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let stubs = degrees
+        .iter()
+        .enumerate()
+        .flat_map(|(u, &d)| std::iter::repeat(u).take(d))
+        .collect::<Vec<_>>();
+    Ok(assemble(degrees.len(), match_stubs(&mut rng, stubs)))
+}
+
+/// A stochastic block model of blocks of `sizes` nodes, where a node of block `a` and
+/// one of block `b` are adjacent with probability `probabilities[a][b]`. Returns the
+/// graph and its blocks.
+pub fn stochastic_block_model(
+    sizes: &[usize],
+    probabilities: &[Vec<f64>],
+    seed: u64,
+) -> anyhow::Result<(Graph, ClusterFrame)> {
+    let k = sizes.len();
+    anyhow::ensure!(
+        probabilities.len() == k && probabilities.iter().all(|row| row.len() == k),
+        "expected a {} by {} probability matrix",
+        k,
+        k
+    );
+    for (a, row) in probabilities.iter().enumerate() {
+        for (b, &p) in row.iter().enumerate() {
+            anyhow::ensure!((0.0..=1.0).contains(&p), "probabilities must be in [0, 1]");
+            anyhow::ensure!(
+                p == probabilities[b][a],
+                "the probability matrix must be symmetric"
+            );
+        }
+    }
+    let offsets = sizes
+        .iter()
+        .scan(0, |offset, &size| {
+            *offset += size;
+            Some(*offset - size)
+        })
+        .collect::<Vec<_>>();
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut edges = vec![];
+    let blocks = sizes.iter().copied().zip(offsets).collect::<Vec<_>>();
+    for (a, &(size_a, offset_a)) in blocks.iter().enumerate() {
+        let within = (size_a * size_a.saturating_sub(1) / 2) as u64;
+        for idx in bernoulli_indices(&mut rng, within, probabilities[a][a]) {
+            let (u, v) = pair_of(idx);
+            edges.push((offset_a + u, offset_a + v));
+        }
+        for (b, &(size_b, offset_b)) in blocks.iter().enumerate().skip(a + 1) {
+            let across = (size_a * size_b) as u64;
+            for idx in bernoulli_indices(&mut rng, across, probabilities[a][b]) {
+                let idx = idx as usize;
+                edges.push((offset_a + idx / size_b, offset_b + idx % size_b));
+            }
+        }
+    }
+    let membership = sizes
+        .iter()
+        .enumerate()
+        .flat_map(|(block, &size)| std::iter::repeat(block as u32).take(size))
+        .collect::<Vec<_>>();
+    planted(membership.len(), edges, &membership)
+}
+
+/// Parameters of `lfr`, named as in the LFR paper where they have a symbol there.
+#[derive(Debug, Clone)]
+pub struct LfrConfig {
+    pub n: usize,
+    pub average_degree: f64,
+    pub max_degree: usize,
+    /// The fraction `mu` of every node's edges leaving its community.
+    pub mixing: f64,
+    /// `tau1`, the exponent of the degree distribution.
+    pub degree_exponent: f64,
+    /// `tau2`, the exponent of the community size distribution.
+    pub community_exponent: f64,
+    /// The smallest community, the smallest degree if `None`.
+    pub min_community: Option<usize>,
+    /// The largest community, the largest degree if `None`.
+    pub max_community: Option<usize>,
+    pub seed: u64,
+}
+
+impl Default for LfrConfig {
+    fn default() -> Self {
+        LfrConfig {
+            n: 1000,
+            average_degree: 10.0,
+            max_degree: 50,
+            mixing: 0.1,
+            degree_exponent: 2.5,
+            community_exponent: 1.5,
+            min_community: None,
+            max_community: None,
+            seed: 0,
+        }
+    }
+}
+
+/// An LFR benchmark graph (Lancichinetti, Fortunato and Radicchi) with its planted
+/// communities: power-law degrees and community sizes, and every node with a fraction
+/// `mixing` of its edges outside its community. Edges are drawn from erased
+/// configuration models (inside every community, and across communities) instead of
+/// LFR's rewiring, so degrees and mixing hold on average rather than exactly.
+pub fn lfr(config: &LfrConfig) -> anyhow::Result<(Graph, ClusterFrame)> {
+    let LfrConfig {
+        n,
+        max_degree,
+        mixing,
+        ..
+    } = *config;
+    anyhow::ensure!(n > 0, "expected at least one node");
+    anyhow::ensure!(
+        (1.0..=max_degree as f64).contains(&config.average_degree),
+        "the average degree must be between 1 and the maximum degree"
+    );
+    anyhow::ensure!((0.0..=1.0).contains(&mixing), "mixing must be in [0, 1]");
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+
+    // degrees: flooring a continuous power law lowers the mean by about a half
+    let min_degree = power_law_min(
+        config.average_degree + 0.5,
+        max_degree as f64 + 1.0,
+        config.degree_exponent,
+    );
+    let degrees = (0..n)
+        .map(|_| {
+            let high = max_degree as f64 + 1.0;
+            let x = power_law(&mut rng, min_degree, high, config.degree_exponent);
+            (x as usize).clamp(1, max_degree)
+        })
+        .collect::<Vec<_>>();
+
+    let min_community = config
+        .min_community
+        .unwrap_or(min_degree as usize)
+        .clamp(1, n);
+    let max_community = config
+        .max_community
+        .unwrap_or(max_degree)
+        .clamp(min_community, n);
+    let sizes = community_sizes(
+        &mut rng,
+        n,
+        min_community,
+        max_community,
+        config.community_exponent,
+    );
+
+    // the internal degree of every node, and a community large enough for it
+    let mut internal = degrees
+        .iter()
+        .map(|&d| ((1.0 - mixing) * d as f64).round() as usize)
+        .collect::<Vec<_>>();
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.shuffle(&mut rng);
+    order.sort_by_key(|&u| std::cmp::Reverse(internal[u]));
+    let mut free = sizes.clone();
+    let mut membership = vec![0u32; n];
+    for u in order {
+        let fits = |c: usize, free: &[usize]| free[c] > 0 && sizes[c] > internal[u];
+        let community = (0..50)
+            .map(|_| rng.gen_range(0..sizes.len()))
+            .find(|&c| fits(c, &free))
+            .or_else(|| (0..sizes.len()).find(|&c| fits(c, &free)))
+            .or_else(|| (0..sizes.len()).find(|&c| free[c] > 0))
+            .expect("community sizes sum to n");
+        internal[u] = internal[u].min(sizes[community] - 1);
+        free[community] -= 1;
+        membership[u] = community as u32;
+    }
+
+    let mut members = vec![vec![]; sizes.len()];
+    for (u, &c) in membership.iter().enumerate() {
+        members[c as usize].push(u);
+    }
+    let mut edges = vec![];
+    for nodes in &members {
+        let mut stubs = nodes
+            .iter()
+            .flat_map(|&u| std::iter::repeat(u).take(internal[u]))
+            .collect::<Vec<_>>();
+        if stubs.len() % 2 == 1 {
+            stubs.pop();
+        }
+        edges.extend(match_stubs(&mut rng, stubs));
+    }
+    let mut stubs = (0..n)
+        .flat_map(|u| std::iter::repeat(u).take(degrees[u] - internal[u]))
+        .collect::<Vec<_>>();
+    if stubs.len() % 2 == 1 {
+        stubs.pop();
+    }
+    edges.extend(
+        match_stubs(&mut rng, stubs)
+            .into_iter()
+            .filter(|&(u, v)| membership[u] != membership[v]),
+    );
+    planted(n, edges, &membership)
+}
+
+/// Community sizes from a power law on `[min, max]` summing to exactly `n`.
+fn community_sizes<R: Rng>(
+    rng: &mut R,
+    n: usize,
+    min: usize,
+    max: usize,
+    exponent: f64,
+) -> Vec<usize> {
+    let mut sizes = vec![];
+    let mut total = 0;
+    while total < n {
+        let size = power_law(rng, min as f64, max as f64 + 1.0, exponent) as usize;
+        let size = size.clamp(min, max).min(n - total);
+        sizes.push(size);
+        total += size;
+    }
+    // a truncated last community below the minimum is spread over the others
+    if sizes.len() > 1 && *sizes.last().unwrap() < min {
+        let rest = sizes.pop().unwrap();
+        let k = sizes.len();
+        for i in 0..rest {
+            sizes[i % k] += 1;
+        }
+    }
+    sizes
+}
+
+/// A sample of the continuous power law `x^-exponent` on `[low, high)`.
+fn power_law<R: Rng>(rng: &mut R, low: f64, high: f64, exponent: f64) -> f64 {
+    let u: f64 = rng.gen();
+    if (exponent - 1.0).abs() < 1e-9 {
+        low * (high / low).powf(u)
+    } else {
+        let e = 1.0 - exponent;
+        (low.powf(e) + u * (high.powf(e) - low.powf(e))).powf(1.0 / e)
+    }
+}
+
+/// The lower end in `[1, high)` of a power law on `[low, high)` with mean `mean`, by
+/// bisection (the mean grows with the lower end).
+fn power_law_min(mean: f64, high: f64, exponent: f64) -> f64 {
+    let integral = |low: f64, e: f64| {
+        if (e + 1.0).abs() < 1e-9 {
+            (high / low).ln()
+        } else {
+            (high.powf(e + 1.0) - low.powf(e + 1.0)) / (e + 1.0)
+        }
+    };
+    let mean_of = |low: f64| integral(low, 1.0 - exponent) / integral(low, -exponent);
+    let (mut lo, mut hi) = (1.0, high - 1e-9);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if mean_of(mid) < mean {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// The indices in `0..total` of independent trials succeeding with probability `p`,
+/// skipping geometrically distributed runs of failures (Batagelj and Brandes).
+fn bernoulli_indices<R: Rng>(rng: &mut R, total: u64, p: f64) -> Vec<u64> {
+    if p <= 0.0 {
+        return vec![];
+    }
+    if p >= 1.0 {
+        return (0..total).collect();
+    }
+    let log_q = (1.0 - p).ln();
+    let mut indices = vec![];
+    let mut next = 0u64;
+    loop {
+        let u: f64 = rng.gen();
+        let skip = ((1.0 - u).ln() / log_q).floor();
+        if skip >= (total - next) as f64 {
+            break indices;
+        }
+        next += skip as u64;
+        indices.push(next);
+        next += 1;
+    }
+}
+
+/// The pair `(u, v)`, `v < u`, numbered `idx` in the order `(1, 0), (2, 0), (2, 1), ...`.
+fn pair_of(idx: u64) -> (usize, usize) {
+    let mut u = ((1.0 + (1.0 + 8.0 * idx as f64).sqrt()) / 2.0) as u64;
+    while u * (u - 1) / 2 > idx {
+        u -= 1;
+    }
+    while (u + 1) * u / 2 <= idx {
+        u += 1;
+    }
+    (u as usize, (idx - u * (u - 1) / 2) as usize)
+}
+
+/// Shuffles `stubs` and pairs them up.
+fn match_stubs<R: Rng>(rng: &mut R, mut stubs: Vec<usize>) -> Vec<(usize, usize)> {
+    stubs.shuffle(rng);
+    stubs.chunks_exact(2).map(|it| (it[0], it[1])).collect()
+}
+
+fn assemble(n: usize, edges: Vec<(usize, usize)>) -> Graph {
+    Graph::from_raw(builder::assemble_from_edges((0..n).collect(), edges))
+}
+
+fn planted(
+    n: usize,
+    edges: Vec<(usize, usize)>,
+    membership: &[u32],
+) -> anyhow::Result<(Graph, ClusterFrame)> {
+    let g = assemble(n, edges);
+    let clusters = membership_to_clusdf(&g, membership)?;
+    Ok((g, clusters))
+}
@@ -0,0 +1,613 @@
+use ahash::{AHashMap, AHashSet};
+use aocluster::{
+    alg::{self, CCLabels},
+    aoc::rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+    belinda::EnrichedGraph,
+};
+use itertools::Itertools;
+use polars::prelude::*;
+use polars::{df, export::once_cell::sync::OnceCell};
+use roaring::RoaringBitmap;
+use std::collections::{hash_map::Entry, VecDeque};
+use std::io::Write;
+use std::sync::Arc;
+use tracing::{info, info_span};
+
+use crate::{
+    biconnected::{self, Biconnectivity},
+    builder,
+    centrality::{self, CentralityColumns},
+    cluster::ClusterFrame,
+    compress,
+    df::{iter_roaring, EfficientSet},
+    directed::{ComponentLabels, Directed},
+    edgelist::{self, GraphReadOptions, GraphReadReport},
+    leiden::{self, LeidenConfig},
+    lpa::{self, LabelPropagationReport},
+    stats::membership_to_clusdf,
+};
+
+/// An undirected graph, relabeled to internal ids `0..n` on load. Cheap to clone.
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+#[derive(Clone)]
+pub struct Graph {
+    pub(crate) data: Arc<EnrichedGraph>,
+    cc: OnceCell<CCLabels>,
+    read_report: Option<GraphReadReport>,
+    directed: Option<Arc<Directed>>,
+    weak_cc: OnceCell<ComponentLabels>,
+    strong_cc: OnceCell<ComponentLabels>,
+    bicc: OnceCell<Biconnectivity>,
+}
+
+impl Graph {
+    pub fn from_raw(graph: aocluster::base::Graph) -> Self {
+        Graph {
+            data: Arc::new(EnrichedGraph::from_graph(graph)),
+            cc: OnceCell::new(),
+            read_report: None,
+            directed: None,
+            weak_cc: OnceCell::new(),
+            strong_cc: OnceCell::new(),
+            bicc: OnceCell::new(),
+        }
+    }
+
+    fn with_arcs(mut self, directed: Option<Directed>) -> Self {
+        self.directed = directed.map(Arc::new);
+        self
+    }
+
+    /// Reads a whitespace-separated edge list, possibly gzip, zstd or bzip2 compressed.
+    pub fn open(filepath: &str) -> anyhow::Result<Self> {
+        Graph::open_with(filepath, &GraphReadOptions::default())
+    }
+
+    /// Reads an edge list as described by `options`.
+    pub fn open_with(filepath: &str, options: &GraphReadOptions) -> anyhow::Result<Self> {
+        let _span = info_span!("read_graph", path = filepath).entered();
+        let (raw, report, directed) = edgelist::read_edgelist(compress::open(filepath)?, options)?;
+        if report.skipped() > 0 {
+            info!(
+                skipped = report.skipped(),
+                blank = report.blank,
+                comments = report.comments,
+                header = report.header,
+                self_loops = report.self_loops,
+                duplicates = report.duplicates,
+                unreciprocated = report.unreciprocated,
+                "skipped edge list lines"
+            );
+        }
+        let mut g = Graph::from_raw(raw).with_arcs(directed);
+        g.read_report = Some(report);
+        info!(n = g.n(), m = g.m(), "graph loaded");
+        Ok(g)
+    }
+
+    /// What was skipped when reading the graph, `None` for graphs not read from a file.
+    pub fn read_report(&self) -> Option<&GraphReadReport> {
+        self.read_report.as_ref()
+    }
+
+    /// The underlying `aocluster` graph.
+    pub fn raw(&self) -> &aocluster::base::Graph {
+        &self.data.graph
+    }
+
+    pub fn n(&self) -> u32 {
+        self.data.graph.n() as u32
+    }
+
+    pub fn m(&self) -> u64 {
+        self.data.graph.m() as u64
+    }
+
+    pub fn get_cc_labels(&self) -> &CCLabels {
+        self.cc.get_or_init(|| alg::cc_labeling(&self.data.graph))
+    }
+
+    /// Biconnected components, bridges and articulation points of the undirected view.
+    pub fn get_biconnectivity(&self) -> &Biconnectivity {
+        self.bicc.get_or_init(|| {
+            let g = &self.data.graph;
+            biconnected::decompose(
+                g.n(),
+                g.m(),
+                |u| g.nodes[u].edges.as_slice(),
+                |u, v| self.edge_id(u, v).unwrap(),
+            )
+        })
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed.is_some()
+    }
+
+    /// The arcs of a graph read with `GraphReadOptions::directed`. Everything else
+    /// (`m`, `get_cc_labels`, the cluster statistics) runs on the undirected view.
+    pub fn directed(&self) -> anyhow::Result<&Directed> {
+        self.directed
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("graph was not read as directed"))
+    }
+
+    pub fn get_weak_cc_labels(&self) -> anyhow::Result<&ComponentLabels> {
+        let directed = self.directed()?;
+        Ok(self.weak_cc.get_or_init(|| directed.weak_components()))
+    }
+
+    pub fn get_strong_cc_labels(&self) -> anyhow::Result<&ComponentLabels> {
+        let directed = self.directed()?;
+        Ok(self.strong_cc.get_or_init(|| directed.strong_components()))
+    }
+
+    pub fn num_components(&self) -> u32 {
+        self.get_cc_labels().num_nodes.len() as u32
+    }
+
+    pub fn largest_component(&self) -> u32 {
+        self.get_cc_labels()
+            .num_nodes
+            .iter()
+            .max()
+            .copied()
+            .unwrap() as u32
+    }
+
+    /// The connected components as a cluster data frame (`label` is the component label
+    /// of `get_cc_labels`), with bounds on the diameter of each component: a double sweep
+    /// gives `diameter_lower`, and `diameter_upper` is twice the smaller eccentricity of
+    /// the first sweep's source and of the midpoint of the second sweep's path. The bounds
+    /// agree on trees and cliques, whose diameter the double sweep finds exactly.
+    pub fn components(&self) -> anyhow::Result<ClusterFrame> {
+        let frame = membership_to_clusdf(self, &self.get_cc_labels().labels)?;
+        let components = iter_roaring(frame.nodes())
+            .map(|it| it.try_into())
+            .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+        let edges = frame.column("m")?.u64()?.into_no_null_iter().collect_vec();
+        let bounds: Vec<(u32, u32)> = components
+            .into_par_iter()
+            .zip(edges)
+            .map(|(nodes, m)| {
+                let n = nodes.len();
+                let start = nodes.min().unwrap() as usize;
+                let (far, ecc, _) = self.farthest(&nodes, start);
+                let (end, sweep, parent) = self.farthest(&nodes, far);
+                if m + 1 == n || 2 * m == n * (n - 1) {
+                    return (sweep, sweep);
+                }
+                let mut mid = end;
+                for _ in 0..sweep / 2 {
+                    mid = parent[nodes.rank(mid as u32) as usize - 1];
+                }
+                let (_, mid_ecc, _) = self.farthest(&nodes, mid);
+                (sweep, 2 * ecc.min(mid_ecc))
+            })
+            .collect();
+        let mut df = frame.into_df();
+        df.with_column(Series::new(
+            "diameter_lower",
+            bounds.iter().map(|it| it.0).collect_vec(),
+        ))?;
+        df.with_column(Series::new(
+            "diameter_upper",
+            bounds.iter().map(|it| it.1).collect_vec(),
+        ))?;
+        ClusterFrame::new(df)
+    }
+
+    /// Breadth-first search from `source` within the connected set `nodes`: a node at
+    /// maximum distance, that distance, and the parent of every node (by rank in `nodes`).
+    fn farthest(&self, nodes: &RoaringBitmap, source: usize) -> (usize, u32, Vec<usize>) {
+        let g = &self.data.graph;
+        let local = |u: usize| nodes.rank(u as u32) as usize - 1;
+        let mut dist = vec![u32::MAX; nodes.len() as usize];
+        let mut parent = vec![source; nodes.len() as usize];
+        let mut queue = VecDeque::from([source]);
+        dist[local(source)] = 0;
+        let mut last = (source, 0);
+        while let Some(u) = queue.pop_front() {
+            let d = dist[local(u)];
+            last = (u, d);
+            for &v in &g.nodes[u].edges {
+                if dist[local(v)] == u32::MAX {
+                    dist[local(v)] = d + 1;
+                    parent[local(v)] = u;
+                    queue.push_back(v);
+                }
+            }
+        }
+        (last.0, last.1, parent)
+    }
+
+    /// One row with `n`, `m`, `num_components` and `largest_component`.
+    pub fn summary(&self) -> anyhow::Result<DataFrame> {
+        Ok(df!(
+            "n" => [self.n()],
+            "m" => [self.m()],
+            "num_components" => [self.num_components()],
+            "largest_component" => [self.largest_component()],
+        )?)
+    }
+
+    /// Writes the graph as a tab-separated edge list of original ids (arcs if directed),
+    /// compressed if `filepath` ends with `.gz`, `.zst` or `.bz2`.
+    pub fn write_edgelist(&self, filepath: &str) -> anyhow::Result<()> {
+        let g = &self.data.graph;
+        let mut w = compress::create(filepath)?;
+        if let Some(directed) = &self.directed {
+            for (u, adj) in directed.out_adj.iter().enumerate() {
+                for &v in adj {
+                    let lhs = g.name_set.rev[u];
+                    let rhs = g.name_set.rev[v as usize];
+                    writeln!(w, "{}\t{}", lhs, rhs)?;
+                }
+            }
+            w.finish()?;
+            return Ok(());
+        }
+        for u in &g.nodes {
+            for v in &u.edges {
+                if u.id < *v {
+                    let lhs = g.name_set.rev[u.id as usize];
+                    let rhs = g.name_set.rev[*v as usize];
+                    writeln!(w, "{}\t{}", lhs, rhs)?;
+                }
+            }
+        }
+        w.finish()?;
+        Ok(())
+    }
+
+    /// The subgraph induced by `nodes` (internal ids), keeping the original node names.
+    pub fn induced_subgraph(&self, nodes: &RoaringBitmap) -> Graph {
+        let g = &self.data.graph;
+        let names = nodes
+            .iter()
+            .map(|u| g.name_set.rev[u as usize])
+            .collect_vec();
+        let adj = nodes
+            .iter()
+            .map(|u| {
+                g.nodes[u as usize]
+                    .edges
+                    .iter()
+                    .filter(|&&v| nodes.contains(v as u32))
+                    .map(|&v| nodes.rank(v as u32) as usize - 1)
+                    .collect_vec()
+            })
+            .collect_vec();
+        let directed = self.directed.as_ref().map(|directed| {
+            let arcs = nodes
+                .iter()
+                .enumerate()
+                .flat_map(|(i, u)| {
+                    directed.out_adj[u as usize]
+                        .iter()
+                        .filter(|&&v| nodes.contains(v))
+                        .map(move |&v| (i, nodes.rank(v) as usize - 1))
+                })
+                .collect_vec();
+            Directed::from_arcs(nodes.len() as usize, &arcs)
+        });
+        Graph::from_raw(builder::assemble(names, adj)).with_arcs(directed)
+    }
+
+    /// Clusters the graph with the Leiden algorithm, optionally starting from the
+    /// `initial` membership (see `ClusterFrame::membership`).
+    pub fn leiden(
+        &self,
+        config: &LeidenConfig,
+        initial: Option<Vec<u32>>,
+    ) -> anyhow::Result<ClusterFrame> {
+        let membership = leiden::leiden(&self.data.graph, config, initial);
+        info!(?config, "leiden finished");
+        membership_to_clusdf(self, &membership)
+    }
+
+    /// Clusters the graph by semi-synchronous label propagation.
+    pub fn label_propagation(
+        &self,
+        seed: u64,
+        max_iterations: usize,
+    ) -> anyhow::Result<(ClusterFrame, LabelPropagationReport)> {
+        let (membership, report) = lpa::label_propagation(&self.data.graph, seed, max_iterations);
+        info!(
+            iterations = report.iterations,
+            converged = report.converged,
+            changed = report.changed,
+            "label propagation finished"
+        );
+        membership_to_clusdf(self, &membership).map(|clus| (clus, report))
+    }
+
+    /// The internal id of the original node id `node`.
+    fn internal_id(&self, node: usize) -> anyhow::Result<usize> {
+        self.data
+            .graph
+            .retrieve(node)
+            .ok_or_else(|| anyhow::anyhow!("node {} is not in the graph", node))
+    }
+
+    /// Breadth-first search from the internal ids `sources`, at most `max_depth` hops:
+    /// the reached nodes with their distances, in the order reached.
+    fn traverse(&self, sources: &[usize], max_depth: Option<u32>) -> Vec<(usize, u32)> {
+        let g = &self.data.graph;
+        let mut seen: AHashSet<usize> = sources.iter().copied().collect();
+        let mut reached = sources.iter().map(|&u| (u, 0)).unique().collect_vec();
+        let mut head = 0;
+        while head < reached.len() {
+            let (u, d) = reached[head];
+            head += 1;
+            if max_depth.map_or(false, |max| d >= max) {
+                continue;
+            }
+            for &v in &g.nodes[u].edges {
+                if seen.insert(v) {
+                    reached.push((v, d + 1));
+                }
+            }
+        }
+        reached
+    }
+
+    /// The nodes within `max_depth` hops of `source` (original ids) with their `distance`,
+    /// in the order a breadth-first search reaches them.
+    pub fn bfs(&self, source: usize, max_depth: Option<u32>) -> anyhow::Result<DataFrame> {
+        let rev = &self.data.graph.name_set.rev;
+        let reached = self.traverse(&[self.internal_id(source)?], max_depth);
+        Ok(df!(
+            "node" => reached.iter().map(|&(u, _)| rev[u] as u32).collect_vec(),
+            "distance" => reached.iter().map(|&(_, d)| d).collect_vec(),
+        )?)
+    }
+
+    /// A shortest path from `a` to `b` (original ids, both included), `None` if they are
+    /// not connected.
+    pub fn shortest_path(&self, a: usize, b: usize) -> anyhow::Result<Option<Vec<usize>>> {
+        let g = &self.data.graph;
+        let (a, b) = (self.internal_id(a)?, self.internal_id(b)?);
+        let mut parent = AHashMap::from_iter([(a, a)]);
+        let mut queue = VecDeque::from([a]);
+        while let Some(u) = queue.pop_front() {
+            if u == b {
+                break;
+            }
+            for &v in &g.nodes[u].edges {
+                if let Entry::Vacant(entry) = parent.entry(v) {
+                    entry.insert(u);
+                    queue.push_back(v);
+                }
+            }
+        }
+        if !parent.contains_key(&b) {
+            return Ok(None);
+        }
+        let mut path = vec![b];
+        while *path.last().unwrap() != a {
+            path.push(parent[path.last().unwrap()]);
+        }
+        Ok(Some(
+            path.into_iter().rev().map(|u| g.name_set.rev[u]).collect(),
+        ))
+    }
+
+    /// The nodes within `radius` hops of any of `nodes` (original ids), as a node set
+    /// like the entries of a `nodes` column.
+    pub fn ego(&self, nodes: &[usize], radius: u32) -> anyhow::Result<EfficientSet> {
+        let sources = nodes
+            .iter()
+            .map(|&u| self.internal_id(u))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let reached = self.traverse(&sources, Some(radius));
+        let set = reached
+            .into_iter()
+            .map(|(u, _)| u as u32)
+            .collect::<RoaringBitmap>();
+        Ok(set.into())
+    }
+
+    /// Endpoints (internal ids, `u < v`) of the edge numbered `e` by the `acc_num_edges` scheme.
+    pub fn edge_endpoints(&self, e: u64) -> (usize, usize) {
+        let g = &self.data.graph;
+        let u = self.data.acc_num_edges.partition_point(|&acc| acc <= e) - 1;
+        let edges = &g.nodes[u].edges;
+        let start = edges.partition_point(|&v| v <= u);
+        (u, edges[start + (e - self.data.acc_num_edges[u]) as usize])
+    }
+
+    /// The inverse of `edge_endpoints`, `None` if `u` and `v` are not adjacent.
+    pub fn edge_id(&self, u: usize, v: usize) -> Option<u64> {
+        let (u, v) = (u.min(v), u.max(v));
+        let edges = &self.data.graph.nodes[u].edges;
+        let start = edges.partition_point(|&w| w <= u);
+        edges[start..]
+            .binary_search(&v)
+            .ok()
+            .map(|offset| self.data.acc_num_edges[u] + offset as u64)
+    }
+
+    /// The articulation points of the graph, as original node ids.
+    pub fn articulation_points(&self) -> Vec<u32> {
+        let rev = &self.data.graph.name_set.rev;
+        self.get_biconnectivity()
+            .articulation_points
+            .iter()
+            .map(|u| rev[u as usize] as u32)
+            .collect()
+    }
+
+    /// Every edge with its biconnected component (`bicc`) and whether it is a bridge;
+    /// only the bridges if `bridges_only`.
+    pub fn biconnected_df(&self, bridges_only: bool) -> anyhow::Result<DataFrame> {
+        let bicc = self.get_biconnectivity();
+        let mut df = self.edges_df(None)?;
+        df.with_column(Series::new("bicc", bicc.edge_components.as_slice()))?;
+        let mut is_bridge = (0..bicc.edge_components.len() as u64)
+            .map(|e| bicc.bridges.contains(e))
+            .collect::<BooleanChunked>();
+        if bridges_only {
+            df = df.filter(&is_bridge)?;
+        } else {
+            is_bridge.rename("bridge");
+            df.with_column(is_bridge.into_series())?;
+        }
+        Ok(df)
+    }
+
+    /// A table of nodes with their degrees, optionally with the adjacency list (`verbose`),
+    /// the labels of the clusters (`label`, `nodes`) containing each node and centrality
+    /// columns.
+    pub fn nodes_df(
+        &self,
+        clustering: Option<(&Series, &Series)>,
+        verbose: bool,
+        centrality: &CentralityColumns,
+    ) -> anyhow::Result<DataFrame> {
+        let g = &self.data.graph;
+        let n = g.n();
+        let nodes = (0..n).map(|it| g.name_set.rev[it] as u32).collect_vec();
+        let degrees = (0..n).map(|it| g.nodes[it].degree() as u32).collect_vec();
+        let mut df = df!(
+            "node" => nodes,
+            "degree" => degrees,
+        )?;
+        let CentralityColumns {
+            tolerance,
+            max_iterations,
+            ..
+        } = *centrality;
+        if let Some(damping) = centrality.pagerank {
+            let scores = centrality::pagerank(g, damping, tolerance, max_iterations);
+            df.with_column(Series::new("pagerank", scores))?;
+        }
+        if centrality.eigenvector {
+            let scores = centrality::eigenvector(g, tolerance, max_iterations);
+            df.with_column(Series::new("eigenvector", scores))?;
+        }
+        if let Some(k) = centrality.k_hop {
+            df.with_column(Series::new("k_hop_degree", centrality::k_hop_degree(g, k)))?;
+        }
+        if let Some(sampling) = centrality.betweenness {
+            let scores = centrality::betweenness(g, sampling, centrality.seed)?;
+            df.with_column(Series::new("betweenness", scores))?;
+        }
+        if verbose {
+            let adj = (0..n)
+                .map(|it| {
+                    g.nodes[it]
+                        .edges
+                        .iter()
+                        .map(|it| g.name_set.rev[*it] as u32)
+                        .collect::<Series>()
+                })
+                .collect_vec();
+            df.with_column(Series::new("adj", adj))?;
+        }
+        if let Some((label, nodes)) = clustering {
+            let label_t = label.dtype();
+            let mut labels_u32: Vec<Vec<Option<u32>>> = vec![vec![]; n];
+            let mut labels_str: Vec<Vec<String>> = vec![vec![]; n];
+            if label_t != &DataType::Utf8 {
+                for (ns, label) in iter_roaring(nodes).zip(label.cast(&DataType::UInt32)?.u32()?) {
+                    let ns: RoaringBitmap = ns.try_into()?;
+                    for node in ns.into_iter() {
+                        labels_u32[node as usize].push(label);
+                    }
+                }
+            } else {
+                for (ns, label) in iter_roaring(nodes).zip(label.utf8()?) {
+                    let ns: RoaringBitmap = ns.try_into()?;
+                    for node in ns.into_iter() {
+                        labels_str[node as usize].push(label.unwrap_or_default().to_string());
+                    }
+                }
+            }
+            let labels_u32 = labels_u32
+                .into_iter()
+                .map(|it| it.into_iter().collect::<Series>())
+                .collect_vec();
+            let labels_str = labels_str
+                .into_iter()
+                .map(|it| it.into_iter().collect::<Series>())
+                .collect_vec();
+            if label_t != &DataType::Utf8 {
+                df.with_column(Series::new("labels", labels_u32))?;
+            } else {
+                df.with_column(Series::new("labels", labels_str))?;
+            }
+        }
+        Ok(df)
+    }
+
+    /// Looks up the edge ids of `(src, dst)` pairs of original ids.
+    pub fn edge_ids_of(&self, src: &Series, dst: &Series) -> anyhow::Result<Vec<Option<u64>>> {
+        let src = src.cast(&DataType::UInt32)?;
+        let dst = dst.cast(&DataType::UInt32)?;
+        let g = &self.data.graph;
+        Ok(src
+            .u32()?
+            .into_iter()
+            .zip(dst.u32()?.into_iter())
+            .map(|(u, v)| {
+                let u = g.retrieve(u? as usize)?;
+                let v = g.retrieve(v? as usize)?;
+                self.edge_id(u, v)
+            })
+            .collect())
+    }
+
+    /// One row per undirected edge, numbered in the same order as the edge sets of
+    /// `covered_edges`. With a clustering (`label`, `nodes`), each endpoint is annotated
+    /// with the label of its first cluster and `intra` marks edges inside some cluster.
+    pub fn edges_df(&self, clustering: Option<(&Series, &Series)>) -> anyhow::Result<DataFrame> {
+        let g = &self.data.graph;
+        let mut src = Vec::with_capacity(g.m());
+        let mut dst = Vec::with_capacity(g.m());
+        for u in &g.nodes {
+            for &v in &u.edges {
+                if u.id < v {
+                    src.push(u.id);
+                    dst.push(v);
+                }
+            }
+        }
+        let mut df = df!(
+            "edge_id" => (0..src.len() as u64).collect_vec(),
+            "src" => src.iter().map(|&u| g.name_set.rev[u] as u32).collect_vec(),
+            "dst" => dst.iter().map(|&v| g.name_set.rev[v] as u32).collect_vec(),
+        )?;
+        if let Some((labels, nodes)) = clustering {
+            let mut memberships: Vec<Vec<u32>> = vec![vec![]; g.n()];
+            for (row, ns) in iter_roaring(nodes).enumerate() {
+                let ns: RoaringBitmap = ns.try_into()?;
+                for u in ns.iter() {
+                    memberships[u as usize].push(row as u32);
+                }
+            }
+            let first_row = |ids: &[usize]| -> IdxCa {
+                ids.iter()
+                    .map(|&u| memberships[u].first().copied())
+                    .collect()
+            };
+            let mut src_label = labels.take(&first_row(&src))?;
+            src_label.rename("src_label");
+            let mut dst_label = labels.take(&first_row(&dst))?;
+            dst_label.rename("dst_label");
+            let intra: Vec<bool> = src
+                .iter()
+                .zip(dst.iter())
+                .map(|(&u, &v)| {
+                    memberships[u]
+                        .iter()
+                        .any(|row| memberships[v].binary_search(row).is_ok())
+                })
+                .collect();
+            df.with_column(src_label)?;
+            df.with_column(dst_label)?;
+            df.with_column(Series::new("intra", intra))?;
+        }
+        Ok(df)
+    }
+}
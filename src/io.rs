@@ -0,0 +1,317 @@
+use aocluster::aoc::rayon::prelude::ParallelIterator;
+use itertools::Itertools;
+use polars::df;
+use polars::prelude::*;
+use roaring::RoaringBitmap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{debug, info, info_span};
+
+use crate::{
+    cluster::ClusterFrame,
+    compress,
+    df::{iter_roaring, EfficientSet, VecEfficientSet},
+    graph::Graph,
+    progress::ProgressSpec,
+    stats::populate_clusdf,
+};
+
+/// Evaluates `$read` (a `DataFrame`) with `$reader` bound to the content of `$path`,
+/// once per chunk if it is compressed (see `compress::Source`), stacking the results.
+macro_rules! read_source {
+    ($path:expr, |$reader:ident| $read:expr) => {
+        match compress::Source::open($path)? {
+            compress::Source::File($reader) => $read,
+            compress::Source::Chunks(chunks) => {
+                let mut stacked: Option<DataFrame> = None;
+                for chunk in chunks {
+                    let $reader = chunk?;
+                    let df = $read;
+                    match stacked.as_mut() {
+                        Some(stacked) => {
+                            stacked.vstack_mut(&df)?;
+                        }
+                        None => stacked = Some(df),
+                    }
+                }
+                let mut df = stacked.ok_or_else(|| anyhow::anyhow!("empty input"))?;
+                df.rechunk();
+                df
+            }
+        }
+    };
+}
+
+/// How clusters of size one, and nodes covered by no cluster, are handled on read.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+pub enum SingletonMode {
+    AutoPopulate,
+    Ignore,
+    AsIs,
+}
+
+pub fn read_json<P: AsRef<Path>>(
+    g: &Graph,
+    filepath: P,
+    mode: SingletonMode,
+    progress: &ProgressSpec,
+) -> anyhow::Result<ClusterFrame> {
+    let _span = info_span!("read_json", path = ?filepath.as_ref()).entered();
+    let mut df = read_source!(filepath, |reader| JsonLineReader::new(reader).finish()?);
+    debug!(rows = df.height(), "parsed json lines");
+    df.with_column(
+        df.column("nodes")?
+            .cast(&DataType::List(Box::new(DataType::UInt32)))?,
+    )?;
+    let mut nodes = node_list_to_bitmaps(g, df.column("nodes")?)?;
+    nodes.rename("nodes");
+    df.with_column(nodes)?;
+    df = postprocess_singleton_mode(g, df, mode)?;
+    populate_clusdf(g, &mut df, progress)?;
+    ClusterFrame::new(df)
+}
+
+/// Postprocesses a data frame with the singleton mode specified
+pub fn postprocess_singleton_mode(
+    g: &Graph,
+    mut df: DataFrame,
+    mode: SingletonMode,
+) -> anyhow::Result<DataFrame> {
+    let _span = info_span!("postprocess_singleton_mode", ?mode).entered();
+    let lb = if mode == SingletonMode::Ignore { 2 } else { 1 };
+    let mask: Series = iter_roaring(df.column("nodes")?)
+        .map(|it| it.len() >= lb)
+        .collect();
+    let before = df.height();
+    df = df.filter(mask.bool()?)?;
+    if df.height() < before {
+        info!(
+            removed = before - df.height(),
+            "removed clusters below minimum size"
+        );
+    }
+    if mode == SingletonMode::AutoPopulate {
+        let covered_nodes: RoaringBitmap = iter_roaring(df.column("nodes")?)
+            .collect_vec()
+            .union()
+            .try_into()?;
+        // create two columns, a column of labels and a column of nodes
+        let mut new_labels = vec![];
+        let mut new_nodes: Vec<EfficientSet> = vec![];
+        if covered_nodes.len() < g.n().into() {
+            for i in 0..g.n() {
+                if !covered_nodes.contains(i) {
+                    new_labels.push(AnyValue::Null);
+                    new_nodes.push(RoaringBitmap::from_iter([i]).into())
+                }
+            }
+        }
+        let new_labels =
+            Series::from_any_values_and_dtype("label", &new_labels, df.column("label")?.dtype())?;
+        let k = new_labels.len();
+        let mut extend_df = df!("label" => new_labels, "nodes" => new_nodes.to_series())?;
+        df.get_column_names_owned().iter().for_each(|col: &String| {
+            if col != "label" && col != "nodes" {
+                let mut null_filled = Vec::with_capacity(k);
+                for _i in 0..k {
+                    null_filled.push(AnyValue::Null);
+                }
+                let s = Series::from_any_values_and_dtype(
+                    col,
+                    &null_filled,
+                    df.column(col).unwrap().dtype(),
+                )
+                .unwrap();
+                extend_df.with_column(s).unwrap();
+            }
+        });
+        df.extend(&extend_df)?;
+        info!(
+            populated = k,
+            "added singleton clusters for uncovered nodes"
+        );
+    }
+    Ok(df)
+}
+
+pub fn read_membership_series(
+    g: &Graph,
+    nodes: &Series,
+    cids: &Series,
+    mode: SingletonMode,
+    progress: &ProgressSpec,
+) -> anyhow::Result<ClusterFrame> {
+    let _span = info_span!("read_membership_series", rows = nodes.len()).entered();
+    let df = df!("nid" => nodes.cast(&DataType::UInt32)?, "cid" => cids)?;
+    let mut df = df
+        .lazy()
+        .groupby(["cid"])
+        .agg([col("nid").list()])
+        .collect()?;
+    let lb = if mode == SingletonMode::Ignore { 2 } else { 1 };
+    let mask: Series = df
+        .column("nid")?
+        .list()?
+        .into_iter()
+        .map(|f| f.map_or(false, |e| e.len() >= lb))
+        .collect();
+    df = df.filter(mask.bool()?)?;
+    debug!(clusters = df.height(), "grouped memberships");
+    let mut nodes = node_list_to_bitmaps(g, df.column("nid")?)?;
+    nodes.rename("nodes");
+    let mut df = df!("label" => df.column("cid")?, "nodes" => nodes)?;
+    df = postprocess_singleton_mode(g, df, mode)?;
+    populate_clusdf(g, &mut df, progress)?;
+    ClusterFrame::new(df)
+}
+
+pub fn read_membership_file(
+    g: &Graph,
+    filepath: &str,
+    sep: u8,
+    mode: SingletonMode,
+    force_string_labels: bool,
+    progress: &ProgressSpec,
+) -> anyhow::Result<ClusterFrame> {
+    let _span = info_span!("read_membership_file", path = filepath).entered();
+    let df = read_membership_table(filepath, sep, force_string_labels)?;
+    let nid = df.column("node")?;
+    let cid = df.column("label")?;
+    read_membership_series(g, nid, cid, mode, progress)
+}
+
+/// Reads the raw pairs of a membership file into the columns `node` and `label`,
+/// without resolving nodes against a graph.
+pub fn read_membership_table(
+    filepath: &str,
+    sep: u8,
+    force_string_labels: bool,
+) -> anyhow::Result<DataFrame> {
+    let label_t = if force_string_labels {
+        DataType::Utf8
+    } else {
+        DataType::UInt32
+    };
+    let mut df = read_source!(filepath, |reader| {
+        CsvReader::new(reader)
+            .has_header(false)
+            .with_delimiter(sep)
+            .with_dtypes_slice(Some(&[DataType::UInt32, label_t]))
+            .finish()?
+    });
+    df.set_column_names(&["node", "label"])?;
+    Ok(df)
+}
+
+/// The extension of `filepath`, looking through compression extensions.
+fn format_extension(filepath: &str) -> Option<&str> {
+    compress::strip_extension(Path::new(filepath))
+        .extension()
+        .and_then(|it| it.to_str())
+}
+
+fn is_json_path(filepath: &str) -> bool {
+    matches!(
+        format_extension(filepath),
+        Some("json" | "jsonl" | "ndjson")
+    )
+}
+
+/// Converts a clustering between the membership format and the JSON format, each side's
+/// format being picked by its extension (`.json`, `.jsonl` or `.ndjson` for JSON). Extra
+/// cluster attributes are dropped when converting to the membership format.
+pub fn convert_clustering(
+    input: &str,
+    output: &str,
+    sep: u8,
+    force_string_labels: bool,
+) -> anyhow::Result<()> {
+    let _span = info_span!("convert_clustering", input, output).entered();
+    let mut df = if is_json_path(input) {
+        read_source!(input, |reader| JsonLineReader::new(reader).finish()?)
+            .select(["label", "nodes"])?
+    } else {
+        read_membership_table(input, sep, force_string_labels)?
+            .lazy()
+            .groupby_stable(["label"])
+            .agg([col("node").list().alias("nodes")])
+            .collect()?
+    };
+    let mut file = compress::create(output)?;
+    if is_json_path(output) {
+        JsonWriter::new(&mut file)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(&mut df)?;
+    } else {
+        let mut df = df.explode(["nodes"])?.select(["nodes", "label"])?;
+        CsvWriter::new(&mut file)
+            .has_header(false)
+            .with_delimiter(sep)
+            .finish(&mut df)?;
+    }
+    file.finish()?;
+    Ok(())
+}
+
+/// Writes a table as Parquet, JSON lines or CSV depending on the extension of `filepath`,
+/// compressed if it ends with `.gz`, `.zst` or `.bz2`.
+pub fn write_table(df: &mut DataFrame, filepath: &str) -> anyhow::Result<()> {
+    let mut file = compress::create(filepath)?;
+    match format_extension(filepath) {
+        Some("parquet") => {
+            ParquetWriter::new(&mut file).finish(df)?;
+        }
+        Some("json" | "jsonl" | "ndjson") => {
+            JsonWriter::new(&mut file)
+                .with_json_format(JsonFormat::JsonLines)
+                .finish(df)?;
+        }
+        Some("tsv") => {
+            CsvWriter::new(&mut file).with_delimiter(b'\t').finish(df)?;
+        }
+        _ => {
+            CsvWriter::new(&mut file).finish(df)?;
+        }
+    }
+    file.finish()?;
+    Ok(())
+}
+
+pub fn node_list_to_bitmaps(g: &Graph, list: &Series) -> anyhow::Result<Series> {
+    let g = &g.data.graph;
+    let as_list = list.list()?;
+    let dropped = AtomicUsize::new(0);
+    let sets: Vec<EfficientSet> = as_list
+        .par_iter()
+        .map(|e| {
+            e.map_or_else(
+                || RoaringBitmap::new().into(),
+                |series| {
+                    let mut seen_nonexistent = false;
+                    let mut bitmap = RoaringBitmap::new();
+                    series.u32().unwrap().into_iter().flatten().for_each(|x| {
+                        match g.retrieve(x as usize) {
+                            Some(internal_id) => {
+                                bitmap.insert(internal_id as u32);
+                            }
+                            None => {
+                                if seen_nonexistent {
+                                    panic!("Nonexistent node that is not singleton: {}", x);
+                                }
+                                seen_nonexistent = true;
+                                dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                    });
+                    bitmap.into()
+                },
+            )
+        })
+        .collect();
+    let dropped = dropped.into_inner();
+    if dropped > 0 {
+        info!(dropped, "dropped dummy nodes not present in the graph");
+    }
+    Ok(sets.to_series())
+}
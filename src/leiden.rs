@@ -0,0 +1,335 @@
+use aocluster::base;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+/// Randomness of the refinement phase, `theta` in the Leiden paper.
+const REFINEMENT_THETA: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    Cpm,
+    Modularity,
+}
+
+impl std::str::FromStr for Objective {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cpm" => Ok(Objective::Cpm),
+            "modularity" | "mod" => Ok(Objective::Modularity),
+            _ => Err(anyhow::anyhow!("Unknown objective: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LeidenConfig {
+    pub objective: Objective,
+    pub resolution: f64,
+    pub seed: u64,
+    pub iterations: usize,
+}
+
+/// Weighted network that the Leiden phases operate on. Level zero is the input graph,
+/// higher levels are aggregates of refined clusters. Self-loops are dropped since
+/// they never change the quality difference of a move.
+struct Network {
+    adj: Vec<Vec<(u32, f64)>>,
+    node_weight: Vec<f64>,
+}
+
+impl Network {
+    fn from_graph(g: &base::Graph, objective: Objective) -> Self {
+        let adj = g
+            .nodes
+            .iter()
+            .map(|u| {
+                u.edges
+                    .iter()
+                    .filter(|&&v| v != u.id)
+                    .map(|&v| (v as u32, 1.0))
+                    .collect()
+            })
+            .collect();
+        let node_weight = g
+            .nodes
+            .iter()
+            .map(|u| match objective {
+                Objective::Cpm => 1.0,
+                Objective::Modularity => u.degree() as f64,
+            })
+            .collect();
+        Network { adj, node_weight }
+    }
+
+    fn len(&self) -> usize {
+        self.node_weight.len()
+    }
+
+    fn aggregate(&self, membership: &[u32], k: usize) -> Network {
+        let mut members = vec![vec![]; k];
+        for (v, &c) in membership.iter().enumerate() {
+            members[c as usize].push(v);
+        }
+        let mut scratch = vec![0.0; k];
+        let mut touched = vec![];
+        let mut adj = Vec::with_capacity(k);
+        let mut node_weight = Vec::with_capacity(k);
+        for (c, vs) in members.iter().enumerate() {
+            let mut w = 0.0;
+            for &v in vs {
+                w += self.node_weight[v];
+                for &(u, ew) in &self.adj[v] {
+                    let d = membership[u as usize];
+                    if d as usize == c {
+                        continue;
+                    }
+                    if scratch[d as usize] == 0.0 {
+                        touched.push(d);
+                    }
+                    scratch[d as usize] += ew;
+                }
+            }
+            adj.push(
+                touched
+                    .drain(..)
+                    .map(|d| (d, std::mem::take(&mut scratch[d as usize])))
+                    .collect(),
+            );
+            node_weight.push(w);
+        }
+        Network { adj, node_weight }
+    }
+}
+
+/// Relabels `membership` in place to `0..k` in order of first appearance, returning `k`.
+fn renumber(membership: &mut [u32]) -> usize {
+    let labels = membership.iter().max().map_or(0, |it| *it as usize + 1);
+    let mut mapping = vec![u32::MAX; labels];
+    let mut k = 0u32;
+    for c in membership.iter_mut() {
+        if mapping[*c as usize] == u32::MAX {
+            mapping[*c as usize] = k;
+            k += 1;
+        }
+        *c = mapping[*c as usize];
+    }
+    k as usize
+}
+
+fn move_nodes_fast(net: &Network, membership: &mut [u32], gamma: f64, rng: &mut ChaCha8Rng) {
+    let n = net.len();
+    let mut cluster_weight = vec![0.0; n];
+    let mut cluster_size = vec![0usize; n];
+    for (v, &c) in membership.iter().enumerate() {
+        cluster_weight[c as usize] += net.node_weight[v];
+        cluster_size[c as usize] += 1;
+    }
+    let mut empty: Vec<u32> = (0..n as u32)
+        .filter(|&c| cluster_size[c as usize] == 0)
+        .collect();
+    let mut order: Vec<u32> = (0..n as u32).collect();
+    order.shuffle(rng);
+    let mut queue: VecDeque<u32> = order.into();
+    let mut in_queue = vec![true; n];
+    let mut neighbor_weight = vec![0.0; n];
+    let mut touched = vec![];
+    while let Some(v) = queue.pop_front() {
+        let v = v as usize;
+        in_queue[v] = false;
+        let current = membership[v];
+        let w = net.node_weight[v];
+        cluster_weight[current as usize] -= w;
+        cluster_size[current as usize] -= 1;
+        for &(u, ew) in &net.adj[v] {
+            let c = membership[u as usize];
+            if neighbor_weight[c as usize] == 0.0 {
+                touched.push(c);
+            }
+            neighbor_weight[c as usize] += ew;
+        }
+        let mut best = current;
+        let mut best_gain =
+            neighbor_weight[current as usize] - gamma * w * cluster_weight[current as usize];
+        for &c in &touched {
+            let gain = neighbor_weight[c as usize] - gamma * w * cluster_weight[c as usize];
+            if gain > best_gain {
+                best = c;
+                best_gain = gain;
+            }
+        }
+        for c in touched.drain(..) {
+            neighbor_weight[c as usize] = 0.0;
+        }
+        if best_gain < 0.0 && cluster_size[current as usize] > 0 {
+            // moving into an empty cluster has zero gain
+            while let Some(c) = empty.pop() {
+                if cluster_size[c as usize] == 0 {
+                    best = c;
+                    break;
+                }
+            }
+        }
+        if best != current {
+            if cluster_size[current as usize] == 0 {
+                empty.push(current);
+            }
+            for &(u, _) in &net.adj[v] {
+                let u = u as usize;
+                if !in_queue[u] && membership[u] != best {
+                    in_queue[u] = true;
+                    queue.push_back(u as u32);
+                }
+            }
+        }
+        membership[v] = best;
+        cluster_weight[best as usize] += w;
+        cluster_size[best as usize] += 1;
+    }
+}
+
+/// Refines each cluster of `membership` starting from singletons, only merging
+/// well-connected subsets so that every refined cluster stays connected.
+fn refine(net: &Network, membership: &[u32], gamma: f64, rng: &mut ChaCha8Rng) -> Vec<u32> {
+    let n = net.len();
+    let mut refined: Vec<u32> = (0..n as u32).collect();
+    let mut refined_weight = net.node_weight.clone();
+    let mut refined_size = vec![1usize; n];
+    let mut cluster_weight = vec![0.0; n];
+    for (v, &c) in membership.iter().enumerate() {
+        cluster_weight[c as usize] += net.node_weight[v];
+    }
+    // weight of edges from each refined cluster to the rest of its enclosing cluster
+    let mut external = vec![0.0; n];
+    for v in 0..n {
+        for &(u, ew) in &net.adj[v] {
+            if membership[u as usize] == membership[v] {
+                external[v] += ew;
+            }
+        }
+    }
+    let mut order: Vec<u32> = (0..n as u32).collect();
+    order.shuffle(rng);
+    let mut neighbor_weight = vec![0.0; n];
+    let mut touched = vec![];
+    let mut candidates: Vec<(u32, f64)> = vec![];
+    for v in order {
+        let v = v as usize;
+        let own = refined[v];
+        if refined_size[own as usize] != 1 {
+            continue;
+        }
+        let s = membership[v] as usize;
+        let w = net.node_weight[v];
+        if external[v] < gamma * w * (cluster_weight[s] - w) {
+            continue;
+        }
+        for &(u, ew) in &net.adj[v] {
+            if membership[u as usize] as usize != s {
+                continue;
+            }
+            let c = refined[u as usize];
+            if neighbor_weight[c as usize] == 0.0 {
+                touched.push(c);
+            }
+            neighbor_weight[c as usize] += ew;
+        }
+        candidates.clear();
+        candidates.push((own, 0.0));
+        for &c in &touched {
+            let rw = refined_weight[c as usize];
+            if c == own || external[c as usize] < gamma * rw * (cluster_weight[s] - rw) {
+                continue;
+            }
+            let gain = neighbor_weight[c as usize] - gamma * w * rw;
+            if gain >= 0.0 {
+                candidates.push((c, gain));
+            }
+        }
+        let max_gain = candidates.iter().map(|it| it.1).fold(0.0, f64::max);
+        let total: f64 = candidates
+            .iter()
+            .map(|it| ((it.1 - max_gain) / REFINEMENT_THETA).exp())
+            .sum();
+        let mut pick = rng.gen::<f64>() * total;
+        let mut chosen = own;
+        for &(c, gain) in &candidates {
+            pick -= ((gain - max_gain) / REFINEMENT_THETA).exp();
+            chosen = c;
+            if pick <= 0.0 {
+                break;
+            }
+        }
+        if chosen != own {
+            let c = chosen as usize;
+            external[c] += external[v] - 2.0 * neighbor_weight[c];
+            refined_weight[c] += w;
+            refined_size[c] += 1;
+            refined_size[own as usize] = 0;
+            refined[v] = chosen;
+        }
+        for c in touched.drain(..) {
+            neighbor_weight[c as usize] = 0.0;
+        }
+    }
+    refined
+}
+
+fn leiden_iteration(
+    base: &Network,
+    mut membership: Vec<u32>,
+    gamma: f64,
+    rng: &mut ChaCha8Rng,
+) -> Vec<u32> {
+    // maps each input node to its node in the current aggregate network
+    let mut projection: Vec<u32> = (0..base.len() as u32).collect();
+    let mut aggregated: Option<Network> = None;
+    loop {
+        let net = aggregated.as_ref().unwrap_or(base);
+        move_nodes_fast(net, &mut membership, gamma, rng);
+        let k = renumber(&mut membership);
+        if k == net.len() {
+            break;
+        }
+        let mut refined = refine(net, &membership, gamma, rng);
+        let k_refined = renumber(&mut refined);
+        if k_refined == net.len() {
+            break;
+        }
+        let next = net.aggregate(&refined, k_refined);
+        let mut next_membership = vec![0; k_refined];
+        for (v, &r) in refined.iter().enumerate() {
+            next_membership[r as usize] = membership[v];
+        }
+        for p in projection.iter_mut() {
+            *p = refined[*p as usize];
+        }
+        membership = next_membership;
+        aggregated = Some(next);
+    }
+    projection
+        .into_iter()
+        .map(|p| membership[p as usize])
+        .collect()
+}
+
+/// Runs Leiden on `g`, optionally starting from `initial` (one label per internal node id).
+/// The returned membership is relabeled to `0..k`.
+pub fn leiden(g: &base::Graph, config: &LeidenConfig, initial: Option<Vec<u32>>) -> Vec<u32> {
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let net = Network::from_graph(g, config.objective);
+    // the modularity used in `exprs.py` is CPM over degrees scaled by 1 / 2m
+    let gamma = match config.objective {
+        Objective::Cpm => config.resolution,
+        Objective::Modularity => config.resolution / (2.0 * g.m() as f64),
+    };
+    let mut membership = initial.unwrap_or_else(|| (0..net.len() as u32).collect());
+    renumber(&mut membership);
+    for _ in 0..config.iterations {
+        membership = leiden_iteration(&net, membership, gamma, &mut rng);
+    }
+    renumber(&mut membership);
+    membership
+}
@@ -1,25 +1,98 @@
-mod df;
+//! Cluster statistics over large graphs. The Rust API mirrors the Python one: load a
+//! [`Graph`], read a clustering into a [`ClusterFrame`], and compute statistics with the
+//! functions of [`stats`]. The pyo3 bindings are built with the `python` feature.
+pub mod biconnected;
+mod builder;
+pub mod centrality;
+pub mod cluster;
+pub mod compare;
+pub mod compress;
+pub mod df;
+pub mod directed;
+pub mod edgelist;
+#[cfg(feature = "python")]
 mod exposure;
+#[cfg(feature = "python")]
 mod ffi;
+pub mod generators;
+pub mod graph;
+pub mod io;
+pub mod leiden;
+#[cfg(feature = "python")]
+mod logging;
+pub mod lpa;
+pub mod null_model;
+pub mod pool;
+pub mod progress;
+mod quotient;
+pub mod stats;
+
+pub use centrality::CentralityColumns;
+pub use cluster::ClusterFrame;
+pub use df::EfficientSet;
+pub use edgelist::{GraphReadOptions, GraphReadReport};
+pub use graph::Graph;
+pub use io::{read_json, read_membership_file, read_membership_series, SingletonMode};
+pub use progress::ProgressSpec;
+
+#[cfg(feature = "python")]
 use exposure::{
-    py_bitmap_union, py_from_memberships, py_label_cc, py_label_cc_size, py_nodeset_to_list,
-    py_popcnt, py_read_membership_file, py_read_json, set_nthreads, Graph, SingletonMode,
+    get_nthreads, pop_nthreads, push_nthreads, py_betweenness_statistics, py_bitmap_union,
+    py_boundary_size, py_centrality_statistics, py_cluster_component_coverage,
+    py_cluster_label_propagation, py_cluster_leiden, py_component_coverage, py_configuration_model,
+    py_cut_statistics, py_directed_statistics, py_edgeset_to_list, py_erdos_renyi,
+    py_from_memberships, py_label_cc, py_label_cc_size, py_label_scc, py_label_wcc, py_lfr,
+    py_nodeset_boundary, py_nodeset_expand, py_nodeset_to_list, py_null_significance,
+    py_open_compressed, py_popcnt, py_quality_statistics, py_quotient_edges, py_quotient_graph,
+    py_read_json, py_read_membership_file, py_stochastic_block_model, set_log_level, set_nthreads,
+    PyCompressedWriter,
 };
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
 /// A Python module implemented in Rust.
+#[cfg(feature = "python")]
 #[pymodule]
-fn belinda(_py: Python, m: &PyModule) -> PyResult<()> {
+fn belinda(py: Python, m: &PyModule) -> PyResult<()> {
+    logging::init(py)?;
     m.add_class::<Graph>()?;
     m.add_class::<SingletonMode>()?;
+    m.add_class::<PyCompressedWriter>()?;
     m.add_function(wrap_pyfunction!(set_nthreads, m)?)?;
+    m.add_function(wrap_pyfunction!(get_nthreads, m)?)?;
+    m.add_function(wrap_pyfunction!(push_nthreads, m)?)?;
+    m.add_function(wrap_pyfunction!(pop_nthreads, m)?)?;
+    m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
     m.add_function(wrap_pyfunction!(py_popcnt, m)?)?;
     m.add_function(wrap_pyfunction!(py_bitmap_union, m)?)?;
     m.add_function(wrap_pyfunction!(py_from_memberships, m)?)?;
     m.add_function(wrap_pyfunction!(py_read_json, m)?)?;
+    m.add_function(wrap_pyfunction!(py_open_compressed, m)?)?;
     m.add_function(wrap_pyfunction!(py_read_membership_file, m)?)?;
     m.add_function(wrap_pyfunction!(py_label_cc, m)?)?;
     m.add_function(wrap_pyfunction!(py_label_cc_size, m)?)?;
     m.add_function(wrap_pyfunction!(py_nodeset_to_list, m)?)?;
+    m.add_function(wrap_pyfunction!(py_nodeset_expand, m)?)?;
+    m.add_function(wrap_pyfunction!(py_nodeset_boundary, m)?)?;
+    m.add_function(wrap_pyfunction!(py_boundary_size, m)?)?;
+    m.add_function(wrap_pyfunction!(py_edgeset_to_list, m)?)?;
+    m.add_function(wrap_pyfunction!(py_cluster_leiden, m)?)?;
+    m.add_function(wrap_pyfunction!(py_cluster_label_propagation, m)?)?;
+    m.add_function(wrap_pyfunction!(py_quotient_edges, m)?)?;
+    m.add_function(wrap_pyfunction!(py_quotient_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(py_label_wcc, m)?)?;
+    m.add_function(wrap_pyfunction!(py_label_scc, m)?)?;
+    m.add_function(wrap_pyfunction!(py_directed_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_cut_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_quality_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_null_significance, m)?)?;
+    m.add_function(wrap_pyfunction!(py_erdos_renyi, m)?)?;
+    m.add_function(wrap_pyfunction!(py_configuration_model, m)?)?;
+    m.add_function(wrap_pyfunction!(py_stochastic_block_model, m)?)?;
+    m.add_function(wrap_pyfunction!(py_lfr, m)?)?;
+    m.add_function(wrap_pyfunction!(py_centrality_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_betweenness_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_cluster_component_coverage, m)?)?;
+    m.add_function(wrap_pyfunction!(py_component_coverage, m)?)?;
     Ok(())
 }
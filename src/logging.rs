@@ -0,0 +1,177 @@
+use polars::export::once_cell::sync::OnceCell;
+use pyo3::prelude::*;
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Instant,
+};
+use tracing::{
+    field::{Field, Visit},
+    span,
+    subscriber::Interest,
+    Event, Level, Metadata, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+/// Name of the Python logger receiving the Rust-side diagnostics.
+pub const LOGGER_NAME: &str = "belinda";
+
+/// The Python logger, looked up once by `init`.
+static LOGGER: OnceCell<PyObject> = OnceCell::new();
+
+/// The Python level below which nothing is forwarded, so that disabled events never
+/// take the GIL. Nothing is forwarded before `init`.
+static LEVEL: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// Installs the layer forwarding `tracing` spans and events to Python's `logging`, at the
+/// effective level of the `belinda` logger. Does nothing if another global subscriber is
+/// already set.
+pub fn init(py: Python) -> PyResult<()> {
+    let logger = py
+        .import("logging")?
+        .call_method1("getLogger", (LOGGER_NAME,))?;
+    LEVEL.store(
+        logger.call_method0("getEffectiveLevel")?.extract()?,
+        Ordering::Relaxed,
+    );
+    let _ = LOGGER.set(logger.into());
+    let subscriber = tracing_subscriber::registry().with(PyLoggingLayer);
+    let _ = tracing::subscriber::set_global_default(subscriber);
+    Ok(())
+}
+
+/// Sets the level of the `belinda` logger and of the records forwarded to it.
+pub fn set_level(py: Python, level: u32) -> PyResult<()> {
+    if let Some(logger) = LOGGER.get() {
+        logger.call_method1(py, "setLevel", (level,))?;
+    }
+    LEVEL.store(level, Ordering::Relaxed);
+    tracing::callsite::rebuild_interest_cache();
+    Ok(())
+}
+
+fn is_enabled(level: u32) -> bool {
+    level >= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Forwards events as log records, and closed spans as `DEBUG` records with their duration.
+/// Events should be emitted from the thread calling into Rust (not from rayon workers)
+/// unless the GIL has been released, as forwarding needs to acquire it.
+struct PyLoggingLayer;
+
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.rest, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.rest, " {}={}", field.name(), value);
+        }
+    }
+}
+
+struct SpanTiming {
+    start: Instant,
+    fields: String,
+}
+
+fn python_level(level: &Level) -> u32 {
+    match *level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        Level::DEBUG => 10,
+        Level::TRACE => 5,
+    }
+}
+
+fn forward(level: u32, message: String) {
+    Python::with_gil(|py| {
+        if let Some(logger) = LOGGER.get() {
+            if let Err(e) = logger.call_method1(py, "log", (level, message)) {
+                e.print(py);
+            }
+        }
+    });
+}
+
+impl<S> Layer<S> for PyLoggingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if is_enabled(python_level(metadata.level())) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        is_enabled(python_level(metadata.level()))
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanTiming {
+                start: Instant::now(),
+                fields: fields.rest,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let scope = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| span.name())
+                    .collect::<Vec<_>>()
+                    .join(":")
+            })
+            .unwrap_or_default();
+        let message = if scope.is_empty() {
+            format!("{}{}", fields.message, fields.rest)
+        } else {
+            format!("[{}] {}{}", scope, fields.message, fields.rest)
+        };
+        forward(python_level(event.metadata().level()), message);
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if !is_enabled(python_level(&Level::DEBUG)) {
+            return;
+        }
+        if let Some(span) = ctx.span(&id) {
+            if let Some(timing) = span.extensions().get::<SpanTiming>() {
+                forward(
+                    python_level(&Level::DEBUG),
+                    format!(
+                        "[{}] finished in {:.3?}{}",
+                        span.name(),
+                        timing.start.elapsed(),
+                        timing.fields
+                    ),
+                );
+            }
+        }
+    }
+}
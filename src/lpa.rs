@@ -0,0 +1,101 @@
+use ahash::AHashMap;
+use aocluster::{
+    aoc::rayon::prelude::{IntoParallelRefIterator, ParallelIterator},
+    base,
+};
+
+#[derive(Debug, Clone)]
+pub struct LabelPropagationReport {
+    pub iterations: usize,
+    pub converged: bool,
+    /// Number of nodes that changed label in the last iteration.
+    pub changed: usize,
+}
+
+fn mix(mut x: u64) -> u64 {
+    // splitmix64 finalizer, used for seeded tie-breaking
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Greedy coloring so that no two nodes of the same class are adjacent.
+fn color_classes(g: &base::Graph) -> Vec<Vec<u32>> {
+    let n = g.n();
+    let mut colors = vec![u32::MAX; n];
+    let mut used = vec![usize::MAX; n + 1];
+    let mut classes: Vec<Vec<u32>> = vec![];
+    for u in 0..n {
+        for &v in &g.nodes[u].edges {
+            if colors[v] != u32::MAX {
+                used[colors[v] as usize] = u;
+            }
+        }
+        let c = (0..).find(|&c| used[c] != u).unwrap();
+        colors[u] = c as u32;
+        if c == classes.len() {
+            classes.push(vec![]);
+        }
+        classes[c].push(u as u32);
+    }
+    classes
+}
+
+/// Semi-synchronous label propagation (Cordasco & Gargano). Nodes within a color class
+/// share no edges, so each class is updated in parallel while staying deterministic
+/// for a given `seed` regardless of the number of threads.
+pub fn label_propagation(
+    g: &base::Graph,
+    seed: u64,
+    max_iterations: usize,
+) -> (Vec<u32>, LabelPropagationReport) {
+    let mut labels: Vec<u32> = (0..g.n() as u32).collect();
+    let classes = color_classes(g);
+    let mut report = LabelPropagationReport {
+        iterations: 0,
+        converged: false,
+        changed: 0,
+    };
+    while report.iterations < max_iterations {
+        let salt = mix(seed ^ mix(report.iterations as u64));
+        report.changed = 0;
+        for class in &classes {
+            let updates: Vec<(u32, u32)> = class
+                .par_iter()
+                .filter_map(|&u| {
+                    let edges = &g.nodes[u as usize].edges;
+                    if edges.is_empty() {
+                        return None;
+                    }
+                    let mut counts: AHashMap<u32, u32> = AHashMap::with_capacity(edges.len());
+                    for &v in edges {
+                        *counts.entry(labels[v]).or_insert(0) += 1;
+                    }
+                    let current = labels[u as usize];
+                    let max = *counts.values().max().unwrap();
+                    if counts.get(&current) == Some(&max) {
+                        return None;
+                    }
+                    let best = counts
+                        .into_iter()
+                        .filter(|&(_, cnt)| cnt == max)
+                        .min_by_key(|&(label, _)| mix(salt ^ label as u64))
+                        .map(|(label, _)| label)
+                        .unwrap();
+                    Some((u, best))
+                })
+                .collect();
+            report.changed += updates.len();
+            for (u, label) in updates {
+                labels[u as usize] = label;
+            }
+        }
+        report.iterations += 1;
+        if report.changed == 0 {
+            report.converged = true;
+            break;
+        }
+    }
+    (labels, report)
+}
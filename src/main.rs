@@ -0,0 +1,161 @@
+use std::io;
+
+use belinda::{
+    compare::compare_memberships,
+    io::{convert_clustering, read_membership_table, write_table},
+    read_membership_file, Graph, ProgressSpec, SingletonMode,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use polars::prelude::*;
+
+/// Cluster statistics for large graphs.
+#[derive(Parser)]
+#[command(name = "belinda", version)]
+struct Cli {
+    /// Show progress bars on stderr.
+    #[arg(long, global = true)]
+    progress: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Computes n, m, c and mcd of every cluster of a membership file.
+    Stats {
+        graph: String,
+        clustering: String,
+        #[arg(long, value_enum, default_value_t = Mode::Asis)]
+        mode: Mode,
+        #[command(flatten)]
+        membership: MembershipArgs,
+        /// Output file (.parquet, .json, .tsv or .csv); CSV on stdout if omitted.
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Compares two membership files (NMI and ARI on the shared nodes).
+    Compare {
+        a: String,
+        b: String,
+        #[command(flatten)]
+        membership: MembershipArgs,
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Converts a clustering between the membership and JSON formats, by extension.
+    Convert {
+        input: String,
+        output: String,
+        #[command(flatten)]
+        membership: MembershipArgs,
+    },
+    /// Prints the size and connectivity of a graph.
+    Summary {
+        graph: String,
+        /// Also write the graph back as a tab-separated edge list.
+        #[arg(long)]
+        edgelist: Option<String>,
+        #[arg(long)]
+        out: Option<String>,
+    },
+}
+
+#[derive(clap::Args)]
+struct MembershipArgs {
+    /// Separator of membership files.
+    #[arg(long, default_value_t = '\t')]
+    sep: char,
+    /// Read cluster labels as strings.
+    #[arg(long)]
+    string_labels: bool,
+}
+
+impl MembershipArgs {
+    fn sep(&self) -> anyhow::Result<u8> {
+        u8::try_from(self.sep)
+            .ok()
+            .filter(u8::is_ascii)
+            .ok_or_else(|| anyhow::anyhow!("--sep must be an ASCII character, got {:?}", self.sep))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mode {
+    Ignore,
+    Asis,
+    Autopopulate,
+}
+
+impl From<Mode> for SingletonMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Ignore => SingletonMode::Ignore,
+            Mode::Asis => SingletonMode::AsIs,
+            Mode::Autopopulate => SingletonMode::AutoPopulate,
+        }
+    }
+}
+
+fn output(mut df: DataFrame, out: Option<&str>) -> anyhow::Result<()> {
+    match out {
+        Some(path) => write_table(&mut df, path),
+        None => Ok(CsvWriter::new(io::stdout().lock()).finish(&mut df)?),
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let progress = if cli.progress {
+        ProgressSpec::Terminal
+    } else {
+        ProgressSpec::Hidden
+    };
+    match cli.command {
+        Command::Stats {
+            graph,
+            clustering,
+            mode,
+            membership,
+            out,
+        } => {
+            let g = Graph::open(&graph)?;
+            let clus = read_membership_file(
+                &g,
+                &clustering,
+                membership.sep()?,
+                mode.into(),
+                membership.string_labels,
+                &progress,
+            )?;
+            let df = clus.into_df().drop("nodes")?;
+            output(df, out.as_deref())
+        }
+        Command::Compare {
+            a,
+            b,
+            membership,
+            out,
+        } => {
+            let sep = membership.sep()?;
+            let a = read_membership_table(&a, sep, membership.string_labels)?;
+            let b = read_membership_table(&b, sep, membership.string_labels)?;
+            output(compare_memberships(&a, &b)?.to_df()?, out.as_deref())
+        }
+        Command::Convert {
+            input,
+            output,
+            membership,
+        } => convert_clustering(&input, &output, membership.sep()?, membership.string_labels),
+        Command::Summary {
+            graph,
+            edgelist,
+            out,
+        } => {
+            let g = Graph::open(&graph)?;
+            if let Some(path) = edgelist {
+                g.write_edgelist(&path)?;
+            }
+            output(g.summary()?, out.as_deref())
+        }
+    }
+}
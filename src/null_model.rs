@@ -0,0 +1,223 @@
+use aocluster::aoc::rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Hypergeometric};
+
+/// The random graphs a cluster's internal edge count is compared against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullModel {
+    /// G(N, M): the graph's edges placed uniformly among all node pairs.
+    ErdosRenyi,
+    /// The graph's stubs matched uniformly, preserving every degree.
+    Configuration,
+}
+
+impl std::str::FromStr for NullModel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "er" | "erdos_renyi" | "gnm" => Ok(NullModel::ErdosRenyi),
+            "configuration" | "config" => Ok(NullModel::Configuration),
+            _ => Err(anyhow::anyhow!(
+                "Unknown null model: {} (expected er or configuration)",
+                s
+            )),
+        }
+    }
+}
+
+/// The size of a cluster: `n` nodes, `m` internal edges and volume (sum of degrees) `vol`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClusterSize {
+    pub n: u64,
+    pub m: u64,
+    pub vol: u64,
+}
+
+/// How surprising a cluster's internal edge count is under a null model: the z-score,
+/// and the p-value of at least that many internal edges. The z-score is `None` when the
+/// null model does not vary.
+#[derive(Debug, Clone, Copy)]
+pub struct Significance {
+    pub z_score: Option<f64>,
+    pub p_value: f64,
+}
+
+impl NullModel {
+    /// Normal approximation (with continuity correction) of the internal edge count of
+    /// every cluster, for a graph of `graph_n` nodes and `graph_m` edges.
+    pub fn analytic(
+        &self,
+        graph_n: u64,
+        graph_m: u64,
+        clusters: &[ClusterSize],
+    ) -> Vec<Significance> {
+        clusters
+            .iter()
+            .map(|cluster| {
+                let (mean, variance) = self.moments(graph_n, graph_m, cluster);
+                let m = cluster.m as f64;
+                if variance <= 0.0 {
+                    return Significance {
+                        z_score: None,
+                        p_value: if m > mean { 0.0 } else { 1.0 },
+                    };
+                }
+                let sd = variance.sqrt();
+                Significance {
+                    z_score: Some((m - mean) / sd),
+                    p_value: normal_sf((m - 0.5 - mean) / sd),
+                }
+            })
+            .collect()
+    }
+
+    /// Mean and variance of the internal edge count of `cluster`.
+    fn moments(&self, graph_n: u64, graph_m: u64, cluster: &ClusterSize) -> (f64, f64) {
+        let total_m = graph_m as f64;
+        match self {
+            NullModel::ErdosRenyi => {
+                // hypergeometric: M edges drawn among P pairs, I of them internal
+                let pairs = pairs(graph_n) as f64;
+                if pairs <= 1.0 {
+                    return (total_m * pairs_of(cluster.n, graph_n) as f64, 0.0);
+                }
+                let share = pairs_of(cluster.n, graph_n) as f64 / pairs;
+                let variance = total_m * share * (1.0 - share) * (pairs - total_m) / (pairs - 1.0);
+                (total_m * share, variance)
+            }
+            NullModel::Configuration => {
+                if graph_m == 0 {
+                    return (0.0, 0.0);
+                }
+                // every edge is internal when both of its stubs are
+                let share = (cluster.vol as f64 / (2.0 * total_m)).powi(2);
+                (total_m * share, total_m * share * (1.0 - share))
+            }
+        }
+    }
+
+    /// Empirical z-scores and p-values from `samples` random graphs, in parallel and
+    /// reproducible from `seed` regardless of the number of threads. The p-value is the
+    /// fraction of samples with at least `m` internal edges, counting the cluster itself.
+    pub fn simulate(
+        &self,
+        graph_n: u64,
+        graph_m: u64,
+        clusters: &[ClusterSize],
+        samples: usize,
+        seed: u64,
+    ) -> anyhow::Result<Vec<Significance>> {
+        anyhow::ensure!(samples > 0, "expected at least one sample");
+        let k = clusters.len();
+        let draws = match self {
+            NullModel::ErdosRenyi => clusters
+                .iter()
+                .map(|cluster| {
+                    let internal = pairs_of(cluster.n, graph_n);
+                    Ok(Some(Hypergeometric::new(
+                        pairs(graph_n),
+                        internal,
+                        graph_m,
+                    )?))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            NullModel::Configuration => vec![None; k],
+        };
+        // per cluster: sum and sum of squares of the draws, and draws of at least `m`
+        let totals = (0..samples as u64)
+            .into_par_iter()
+            .fold(
+                || vec![(0u64, 0u128, 0u64); k],
+                |mut totals, i| {
+                    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                    rng.set_stream(i);
+                    for (j, cluster) in clusters.iter().enumerate() {
+                        let x = match &draws[j] {
+                            Some(hypergeometric) => hypergeometric.sample(&mut rng),
+                            None => match_stubs(&mut rng, cluster.vol, 2 * graph_m),
+                        };
+                        let (sum, squares, extreme) = &mut totals[j];
+                        *sum += x;
+                        *squares += (x as u128) * (x as u128);
+                        *extreme += (x >= cluster.m) as u64;
+                    }
+                    totals
+                },
+            )
+            .reduce(
+                || vec![(0u64, 0u128, 0u64); k],
+                |a, b| {
+                    a.into_iter()
+                        .zip(b)
+                        .map(|(a, b)| (a.0 + b.0, a.1 + b.1, a.2 + b.2))
+                        .collect()
+                },
+            );
+        let count = samples as f64;
+        Ok(clusters
+            .iter()
+            .zip(totals)
+            .map(|(cluster, (sum, squares, extreme))| {
+                let mean = sum as f64 / count;
+                let variance = (squares as f64 / count - mean * mean).max(0.0);
+                Significance {
+                    z_score: (variance > 0.0).then(|| (cluster.m as f64 - mean) / variance.sqrt()),
+                    p_value: (extreme + 1) as f64 / (count + 1.0),
+                }
+            })
+            .collect())
+    }
+}
+
+/// The number of internal edges when `inside` of `stubs` stubs are matched uniformly:
+/// follow each inside stub to a uniformly random partner among the unmatched stubs.
+fn match_stubs<R: Rng>(rng: &mut R, mut inside: u64, mut stubs: u64) -> u64 {
+    let mut internal = 0;
+    while inside > 0 && stubs > 1 {
+        if rng.gen_range(0..stubs - 1) < inside - 1 {
+            internal += 1;
+            inside -= 2;
+        } else {
+            inside -= 1;
+        }
+        stubs -= 2;
+    }
+    internal
+}
+
+fn pairs(n: u64) -> u64 {
+    n * n.saturating_sub(1) / 2
+}
+
+/// Node pairs inside a cluster of `n` nodes, capped for inconsistent inputs.
+fn pairs_of(n: u64, graph_n: u64) -> u64 {
+    pairs(n.min(graph_n))
+}
+
+/// The upper tail of the standard normal distribution.
+fn normal_sf(z: f64) -> f64 {
+    0.5 * erfc(z / std::f64::consts::SQRT_2)
+}
+
+/// The complementary error function, with a relative error below `1.2e-7`
+/// (Numerical Recipes' `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let tail = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        tail
+    } else {
+        2.0 - tail
+    }
+}
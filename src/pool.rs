@@ -0,0 +1,102 @@
+use aocluster::aoc::rayon::{self, ThreadPool};
+use polars::export::once_cell::sync::Lazy;
+#[cfg(feature = "python")]
+use pyo3::Python;
+use std::{
+    cell::RefCell,
+    sync::{Arc, RwLock},
+};
+
+/// Environment variable read for the default number of threads.
+pub const NUM_THREADS_ENV: &str = "BELINDA_NUM_THREADS";
+
+static POOL: Lazy<RwLock<Arc<ThreadPool>>> =
+    Lazy::new(|| RwLock::new(build_pool(default_nthreads())));
+
+thread_local! {
+    /// Pools pushed by `push_scoped` on this thread, innermost last.
+    static SCOPED: RefCell<Vec<Arc<ThreadPool>>> = RefCell::new(vec![]);
+}
+
+/// The number of threads from `BELINDA_NUM_THREADS`, `0` (rayon's default) if unset.
+pub fn default_nthreads() -> usize {
+    std::env::var(NUM_THREADS_ENV)
+        .ok()
+        .and_then(|it| it.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn build_pool(nthreads: usize) -> Arc<ThreadPool> {
+    Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(nthreads)
+            .thread_name(|i| format!("belinda-{}", i))
+            .build()
+            .expect("failed to build thread pool"),
+    )
+}
+
+/// Replaces the process-wide pool used by subsequent computations. Computations
+/// already running keep the pool they started on, and threads inside a scoped pool
+/// keep using it.
+pub fn set_nthreads(nthreads: usize) {
+    let pool = build_pool(nthreads);
+    *POOL.write().unwrap() = pool;
+}
+
+/// Makes computations started from the calling thread use a pool of `nthreads`
+/// threads until the matching `pop_scoped`, without affecting other threads.
+pub fn push_scoped(nthreads: usize) {
+    let pool = build_pool(nthreads);
+    SCOPED.with(|scoped| scoped.borrow_mut().push(pool));
+}
+
+/// Ends the innermost `push_scoped` of the calling thread, if any.
+pub fn pop_scoped() {
+    SCOPED.with(|scoped| scoped.borrow_mut().pop());
+}
+
+/// Runs `f` with computations started from the calling thread on `nthreads` threads.
+pub fn scoped<T>(nthreads: usize, f: impl FnOnce() -> T) -> T {
+    struct Pop;
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            pop_scoped();
+        }
+    }
+    push_scoped(nthreads);
+    let _pop = Pop;
+    f()
+}
+
+/// The pool computations started from the calling thread run on: the innermost
+/// scoped pool, else the process-wide one.
+pub fn current() -> Arc<ThreadPool> {
+    SCOPED
+        .with(|scoped| scoped.borrow().last().cloned())
+        .unwrap_or_else(|| POOL.read().unwrap().clone())
+}
+
+pub fn nthreads() -> usize {
+    current().current_num_threads()
+}
+
+/// Runs `f` on the configured thread pool.
+pub fn install<T, F>(f: F) -> T
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    current().install(f)
+}
+
+/// Releases the GIL and runs `f` on the configured thread pool.
+#[cfg(feature = "python")]
+pub fn run<T, F>(py: Python, f: F) -> T
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    let pool = current();
+    py.allow_threads(|| pool.install(f))
+}
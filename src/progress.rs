@@ -0,0 +1,190 @@
+use indicatif::{ProgressBar, ProgressStyle};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Python callbacks are invoked at most this many times per task.
+#[cfg(feature = "python")]
+const CALLBACK_STEPS: u64 = 100;
+
+/// How progress should be reported, parsed from the `progress` argument of Python functions:
+/// `True` draws indicatif bars on stderr, a tqdm-like object (having `update`) is updated
+/// in place, and any other callable is called as `callback(done, total)`.
+#[derive(Clone)]
+pub enum ProgressSpec {
+    Hidden,
+    Terminal,
+    #[cfg(feature = "python")]
+    Callback(PyObject),
+}
+
+impl ProgressSpec {
+    #[cfg(feature = "python")]
+    pub fn from_py(progress: Option<&PyAny>) -> PyResult<Self> {
+        let progress = match progress {
+            Some(progress) if !progress.is_none() => progress,
+            _ => return Ok(ProgressSpec::Hidden),
+        };
+        if let Ok(enabled) = progress.extract::<bool>() {
+            return Ok(if enabled {
+                ProgressSpec::Terminal
+            } else {
+                ProgressSpec::Hidden
+            });
+        }
+        if progress.hasattr("update")? || progress.is_callable() {
+            Ok(ProgressSpec::Callback(progress.into()))
+        } else {
+            Err(pyo3::exceptions::PyTypeError::new_err(
+                "progress must be a bool, a tqdm-like object or a callable",
+            ))
+        }
+    }
+
+    /// Starts tracking a task of `total` steps, or an open-ended one if `None`.
+    pub fn start(&self, total: Option<u64>, message: &'static str) -> Progress {
+        let bar = match self {
+            ProgressSpec::Terminal => Some(match total {
+                Some(total) => {
+                    let bar = ProgressBar::new(total);
+                    bar.set_style(
+                        ProgressStyle::with_template(
+                            "{msg} {wide_bar} {pos}/{len} [{elapsed_precise}<{eta_precise}]",
+                        )
+                        .unwrap(),
+                    );
+                    bar
+                }
+                None => {
+                    let bar = ProgressBar::new_spinner();
+                    bar.enable_steady_tick(Duration::from_millis(100));
+                    bar
+                }
+            }),
+            _ => None,
+        };
+        if let Some(bar) = &bar {
+            bar.set_message(message);
+        }
+        Progress {
+            bar,
+            #[cfg(feature = "python")]
+            callback: match self {
+                ProgressSpec::Callback(callback) => {
+                    Some(Callback::start(callback.clone(), total, message))
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A running task. Safe to advance from the rayon workers, as long as the GIL
+/// has been released by the calling thread (see `pool::run`).
+pub struct Progress {
+    bar: Option<ProgressBar>,
+    #[cfg(feature = "python")]
+    callback: Option<Callback>,
+}
+
+impl Progress {
+    pub fn inc(&self, delta: u64) {
+        if let Some(bar) = &self.bar {
+            bar.inc(delta);
+        }
+        #[cfg(feature = "python")]
+        if let Some(callback) = &self.callback {
+            callback.inc(delta);
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+        #[cfg(feature = "python")]
+        if let Some(callback) = &self.callback {
+            callback.finish();
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+struct Callback {
+    object: PyObject,
+    total: u64,
+    step: u64,
+    done: AtomicU64,
+    reported: AtomicU64,
+}
+
+#[cfg(feature = "python")]
+impl Callback {
+    fn start(object: PyObject, total: Option<u64>, message: &'static str) -> Self {
+        Python::with_gil(|py| -> PyResult<()> {
+            let object = object.as_ref(py);
+            if object.hasattr("update")? {
+                object.setattr("total", total)?;
+                if object.hasattr("set_description")? {
+                    object.call_method1("set_description", (message,))?;
+                }
+            }
+            Ok(())
+        })
+        .ok();
+        let total = total.unwrap_or(1);
+        Callback {
+            object,
+            total,
+            step: (total / CALLBACK_STEPS).max(1),
+            done: AtomicU64::new(0),
+            reported: AtomicU64::new(0),
+        }
+    }
+
+    fn inc(&self, delta: u64) {
+        let done = self.done.fetch_add(delta, Ordering::Relaxed) + delta;
+        let reported = self.reported.load(Ordering::Relaxed);
+        // another thread may already have reported past `done`
+        if done.saturating_sub(reported) >= self.step || (done >= self.total && done > reported) {
+            self.report(done);
+        }
+    }
+
+    fn report(&self, done: u64) {
+        // `reported` only moves forwards, and each thread calls back into Python with
+        // exactly the progress it moved it by
+        let mut reported = self.reported.load(Ordering::Relaxed);
+        loop {
+            if done <= reported {
+                return;
+            }
+            match self.reported.compare_exchange_weak(
+                reported,
+                done,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => reported = current,
+            }
+        }
+        Python::with_gil(|py| {
+            let object = self.object.as_ref(py);
+            let result = if object.hasattr("update").unwrap_or(false) {
+                object.call_method1("update", (done - reported,))
+            } else {
+                object.call1((done, self.total))
+            };
+            if let Err(e) = result {
+                e.print(py);
+            }
+        });
+    }
+
+    fn finish(&self) {
+        self.report(self.total.max(self.done.load(Ordering::Relaxed)));
+    }
+}
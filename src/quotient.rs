@@ -0,0 +1,53 @@
+use ahash::AHashMap;
+use aocluster::{
+    aoc::rayon::prelude::{IntoParallelIterator, ParallelIterator},
+    base,
+};
+use roaring::RoaringBitmap;
+
+/// Maps every node to the first cluster (by row) containing it, if any.
+pub fn cluster_owner(n: usize, clusters: &[RoaringBitmap]) -> Vec<Option<u32>> {
+    let mut owner = vec![None; n];
+    for (row, nodes) in clusters.iter().enumerate() {
+        for u in nodes.iter() {
+            owner[u as usize].get_or_insert(row as u32);
+        }
+    }
+    owner
+}
+
+/// Counts the edges running within and between clusters, i.e. splits the edges among
+/// the clustered nodes by the pair of clusters at their ends. Pairs are returned as
+/// `(a, b, n_edges)` with `a <= b` by row. Nodes in several clusters are attributed to
+/// the first one, for internal and cross edges alike, so the counts sum to the number of
+/// edges among clustered nodes; edges leaving the clustered nodes are not counted.
+pub fn edge_counts(g: &base::Graph, clusters: &[RoaringBitmap]) -> Vec<(u32, u32, u64)> {
+    let owner = cluster_owner(g.n(), clusters);
+    let counts = (0..g.n())
+        .into_par_iter()
+        .fold(AHashMap::new, |mut acc, u| {
+            if let Some(a) = owner[u] {
+                for &v in &g.nodes[u].edges {
+                    match owner[v] {
+                        Some(b) if u < v => {
+                            *acc.entry((a.min(b), a.max(b))).or_insert(0u64) += 1;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            acc
+        })
+        .reduce(AHashMap::new, |mut lhs, rhs| {
+            for (k, v) in rhs {
+                *lhs.entry(k).or_insert(0) += v;
+            }
+            lhs
+        });
+    let mut out = counts
+        .into_iter()
+        .map(|((a, b), k)| (a, b, k))
+        .collect::<Vec<_>>();
+    out.sort_unstable();
+    out
+}
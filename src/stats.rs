@@ -0,0 +1,713 @@
+use aocluster::{
+    aoc::rayon::prelude::{IntoParallelIterator, ParallelBridge, ParallelIterator},
+    belinda::EnrichedGraph,
+};
+use itertools::Itertools;
+use polars::df;
+use polars::prelude::*;
+use roaring::{MultiOps, RoaringBitmap, RoaringTreemap};
+use tracing::info_span;
+
+use crate::{
+    biconnected::Adjacency,
+    builder,
+    centrality::{self, BetweennessSampling},
+    cluster::ClusterFrame,
+    df::{
+        build_series_from_bitmap, build_series_from_sets, iter_roaring, EfficientSet,
+        VecEfficientSet,
+    },
+    graph::Graph,
+    null_model::{ClusterSize, NullModel},
+    progress::ProgressSpec,
+    quotient,
+};
+
+/// Adds the `n`, `m`, `c` and `mcd` columns to a frame with a `nodes` column.
+pub fn populate_clusdf(
+    g: &Graph,
+    df: &mut DataFrame,
+    progress: &ProgressSpec,
+) -> anyhow::Result<()> {
+    let g = &g.data.graph;
+    let bitmaps: Vec<RoaringBitmap> = iter_roaring(df.column("nodes")?)
+        .map(|n| n.try_into().unwrap())
+        .collect_vec();
+    let _span = info_span!("populate_clusdf", clusters = bitmaps.len()).entered();
+    let edges_bitmaps = adjacency_bitmaps(g);
+    let progress = progress.start(Some(bitmaps.len() as u64), "computing statistics");
+    let data: Vec<_> = bitmaps
+        .into_par_iter()
+        .map(|nodes| {
+            let mut m = 0u64;
+            let mut c = 0u64;
+            let mut mcd = (g.m() + 1) as u64;
+            for u in nodes.iter() {
+                let adj = &edges_bitmaps[u as usize];
+                let ic = adj.intersection_len(&nodes);
+                m += ic;
+                c += adj.len() as u64 - ic;
+                mcd = mcd.min(ic);
+            }
+            if mcd == (g.m() + 1) as u64 {
+                mcd = 0;
+            }
+            m /= 2;
+            progress.inc(1);
+            (nodes.len(), m, c, mcd)
+        })
+        .collect();
+    progress.finish();
+    let mut n_s = Vec::with_capacity(data.len());
+    let mut m_s = Vec::with_capacity(data.len());
+    let mut c_s = Vec::with_capacity(data.len());
+    let mut mcd_s = Vec::with_capacity(data.len());
+    for (n, m, c, mcd) in data {
+        n_s.push(n);
+        m_s.push(m);
+        c_s.push(c);
+        mcd_s.push(mcd);
+    }
+    df.with_column(Series::new("n", n_s))?;
+    df.with_column(Series::new("m", m_s))?;
+    df.with_column(Series::new("c", c_s))?;
+    df.with_column(Series::new("mcd", mcd_s))?;
+    Ok(())
+}
+
+/// The neighbors of every node as a bitmap.
+fn adjacency_bitmaps(g: &aocluster::base::Graph) -> Vec<RoaringBitmap> {
+    g.nodes
+        .iter()
+        .map(|n| RoaringBitmap::from_sorted_iter(n.edges.iter().map(|it| *it as u32)).unwrap())
+        .collect_vec()
+}
+
+/// Yang & Leskovec's scoring functions of every cluster, null where undefined (e.g. the
+/// `separability` of a cluster with no cut edges):
+///
+///  - `internal_density`: `m` over the number of node pairs of the cluster.
+///  - `expansion`: cut edges per node, `c / n`.
+///  - `cut_ratio`: `c` over the number of node pairs across the cluster boundary.
+///  - `normalized_cut`: `c / (2m + c) + c / (2(M - m) + c)` for a graph of `M` edges.
+///  - `separability`: `m / c`.
+///  - `fomd`: the fraction of nodes with more neighbors inside than the median degree of
+///    the graph.
+///  - `tpr`: the fraction of nodes on a triangle inside the cluster.
+///  - `max_odf`, `avg_odf`: the largest and the average fraction of a node's neighbors
+///    outside the cluster.
+///  - `flake_odf`: the fraction of nodes with fewer neighbors inside than outside.
+pub fn quality_statistics(
+    g: &Graph,
+    nodes: &Series,
+    progress: &ProgressSpec,
+) -> anyhow::Result<DataFrame> {
+    let g = &g.data.graph;
+    let bitmaps = iter_roaring(nodes)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let _span = info_span!("quality_statistics", clusters = bitmaps.len()).entered();
+    let edges_bitmaps = adjacency_bitmaps(g);
+    let median_degree = {
+        let degrees = g
+            .nodes
+            .iter()
+            .map(|it| it.degree())
+            .sorted_unstable()
+            .collect_vec();
+        match degrees.len() {
+            0 => 0.0,
+            len if len % 2 == 1 => degrees[len / 2] as f64,
+            len => (degrees[len / 2 - 1] + degrees[len / 2]) as f64 / 2.0,
+        }
+    };
+    let total_n = g.n() as f64;
+    let total_m = g.m() as f64;
+    let ratio = |num: f64, den: f64| if den > 0.0 { Some(num / den) } else { None };
+    let progress = progress.start(Some(bitmaps.len() as u64), "computing quality measures");
+    let rows: Vec<[Option<f64>; 10]> = bitmaps
+        .into_par_iter()
+        .map(|nodes| {
+            let n = nodes.len() as f64;
+            let (mut m, mut c) = (0u64, 0u64);
+            let (mut above_median, mut on_triangle, mut flake) = (0u64, 0u64, 0u64);
+            let (mut max_odf, mut sum_odf) = (None, 0.0);
+            for u in nodes.iter() {
+                let adj = &edges_bitmaps[u as usize];
+                let inside = adj & &nodes;
+                let degree = adj.len();
+                let internal = inside.len();
+                m += internal;
+                c += degree - internal;
+                if internal as f64 > median_degree {
+                    above_median += 1;
+                }
+                if 2 * internal < degree {
+                    flake += 1;
+                }
+                if inside
+                    .iter()
+                    .any(|v| edges_bitmaps[v as usize].intersection_len(&inside) > 0)
+                {
+                    on_triangle += 1;
+                }
+                if let Some(odf) = ratio((degree - internal) as f64, degree as f64) {
+                    max_odf = Some(max_odf.map_or(odf, |it: f64| it.max(odf)));
+                    sum_odf += odf;
+                }
+            }
+            let m = (m / 2) as f64;
+            let c = c as f64;
+            progress.inc(1);
+            [
+                ratio(m, n * (n - 1.0) / 2.0),
+                ratio(c, n),
+                ratio(c, n * (total_n - n)),
+                ratio(c, 2.0 * m + c)
+                    .zip(ratio(c, 2.0 * (total_m - m) + c))
+                    .map(|(a, b)| a + b),
+                ratio(m, c),
+                ratio(above_median as f64, n),
+                ratio(on_triangle as f64, n),
+                max_odf,
+                ratio(sum_odf, n),
+                ratio(flake as f64, n),
+            ]
+        })
+        .collect();
+    progress.finish();
+    let column = |i: usize| rows.iter().map(|row| row[i]).collect_vec();
+    Ok(df!(
+        "internal_density" => column(0),
+        "expansion" => column(1),
+        "cut_ratio" => column(2),
+        "normalized_cut" => column(3),
+        "separability" => column(4),
+        "fomd" => column(5),
+        "tpr" => column(6),
+        "max_odf" => column(7),
+        "avg_odf" => column(8),
+        "flake_odf" => column(9),
+    )?)
+}
+
+/// Builds a cluster data frame from a membership vector indexed by internal node id.
+/// Clusters are relabeled to `0..k` in order of their original labels.
+pub fn membership_to_clusdf(g: &Graph, membership: &[u32]) -> anyhow::Result<ClusterFrame> {
+    let k = membership.iter().max().map_or(0, |it| *it as usize + 1);
+    let mut bitmaps = vec![RoaringBitmap::new(); k];
+    for (u, &c) in membership.iter().enumerate() {
+        bitmaps[c as usize].insert(u as u32);
+    }
+    let nodes: Vec<EfficientSet> = bitmaps
+        .into_iter()
+        .filter(|it| !it.is_empty())
+        .map(|it| it.into())
+        .collect();
+    let k = nodes.len();
+    let mut df = df!(
+        "label" => (0..k as u32).collect::<Vec<_>>(),
+        "nodes" => nodes.to_series(),
+    )?;
+    populate_clusdf(g, &mut df, &ProgressSpec::Hidden)?;
+    ClusterFrame::new(df)
+}
+
+/// Reads the `nodes` column of a cluster data frame back into a membership vector.
+/// Nodes in several clusters keep the first, and nodes covered by no cluster are given
+/// fresh singleton labels, so the labels are exactly `0..k`.
+pub fn clusdf_to_membership(g: &Graph, nodes: &Series) -> anyhow::Result<Vec<u32>> {
+    let mut membership = vec![u32::MAX; g.n() as usize];
+    let mut k = 0u32;
+    for ns in iter_roaring(nodes) {
+        let ns: RoaringBitmap = ns.try_into()?;
+        let mut claimed = false;
+        for u in ns.iter() {
+            if membership[u as usize] == u32::MAX {
+                membership[u as usize] = k;
+                claimed = true;
+            }
+        }
+        if claimed {
+            k += 1;
+        }
+    }
+    for c in membership.iter_mut().filter(|it| **it == u32::MAX) {
+        *c = k;
+        k += 1;
+    }
+    Ok(membership)
+}
+
+pub fn label_cc(g: &Graph, series: &Series) -> anyhow::Result<Series> {
+    let labels = &g.get_cc_labels().labels;
+    let g = &g.data.graph;
+    let mut ans = vec![];
+    for u in series.u32().into_iter() {
+        for v in u {
+            ans.push(v.map(|v| labels[g.retrieve(v as usize).unwrap() as usize]));
+        }
+    }
+    Ok(Series::new("cc", ans))
+}
+
+pub fn label_cc_size(g: &Graph, series: &Series) -> anyhow::Result<Series> {
+    let num_nodes = &g.get_cc_labels().num_nodes;
+    let mut ans = vec![];
+    for u in series.u32().into_iter() {
+        for v in u {
+            ans.push(v.map(|v| num_nodes[v as usize]));
+        }
+    }
+    Ok(Series::new("cc_size", ans))
+}
+
+/// Labels a series of original node ids with their weakly (`strong = false`) or strongly
+/// connected component in a directed graph.
+pub fn label_directed_cc(g: &Graph, series: &Series, strong: bool) -> anyhow::Result<Series> {
+    let labels = if strong {
+        &g.get_strong_cc_labels()?.labels
+    } else {
+        &g.get_weak_cc_labels()?.labels
+    };
+    let raw = &g.data.graph;
+    let ans: Vec<Option<u32>> = series
+        .cast(&DataType::UInt32)?
+        .u32()?
+        .into_iter()
+        .map(|v| v.and_then(|v| raw.retrieve(v as usize)).map(|u| labels[u]))
+        .collect();
+    Ok(Series::new(if strong { "scc" } else { "wcc" }, ans))
+}
+
+/// Directed statistics of every cluster of a directed graph: internal arcs (`m_arcs`),
+/// arcs leaving (`out_cut`) and entering (`in_cut`) the cluster, and its directed
+/// modularity and conductance.
+pub fn directed_cluster_stats(
+    g: &Graph,
+    nodes: &Series,
+    resolution: f64,
+) -> anyhow::Result<DataFrame> {
+    let directed = g.directed()?;
+    let clusters = iter_roaring(nodes)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let arcs = directed.cluster_arcs(&clusters);
+    let m = directed.m();
+    Ok(df!(
+        "m_arcs" => arcs.iter().map(|it| it.internal).collect_vec(),
+        "out_cut" => arcs.iter().map(|it| it.out_cut).collect_vec(),
+        "in_cut" => arcs.iter().map(|it| it.in_cut).collect_vec(),
+        "directed_modularity" => arcs.iter().map(|it| it.modularity(m, resolution)).collect_vec(),
+        "directed_conductance" => arcs.iter().map(|it| it.conductance(m)).collect_vec(),
+    )?)
+}
+
+/// Cut vertices (`n_articulation_points`) and cut edges (`n_bridges`) of the subgraph
+/// induced by every cluster. A connected cluster with either is held together by a
+/// single node or edge.
+pub fn cut_statistics(g: &Graph, nodes: &Series) -> anyhow::Result<DataFrame> {
+    let raw = &g.data.graph;
+    let clusters = iter_roaring(nodes)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let _span = info_span!("cut_statistics", clusters = clusters.len()).entered();
+    let counts: Vec<(u32, u64)> = clusters
+        .into_par_iter()
+        .map(|nodes| {
+            let adj = nodes
+                .iter()
+                .map(|u| {
+                    raw.nodes[u as usize]
+                        .edges
+                        .iter()
+                        .filter(|&&v| nodes.contains(v as u32))
+                        .map(|&v| nodes.rank(v as u32) as usize - 1)
+                        .collect_vec()
+                })
+                .collect_vec();
+            let bicc = Adjacency::new(adj).decompose();
+            (bicc.articulation_points.len() as u32, bicc.bridges.len())
+        })
+        .collect();
+    Ok(df!(
+        "n_articulation_points" => counts.iter().map(|it| it.0).collect_vec(),
+        "n_bridges" => counts.iter().map(|it| it.1).collect_vec(),
+    )?)
+}
+
+/// How every cluster sits across the connected components: the number of components
+/// it touches (`n_components`) and the fraction of those components' nodes it covers
+/// (`component_coverage`, 1 for a cluster that is a whole component).
+pub fn cluster_component_coverage(g: &Graph, nodes: &Series) -> anyhow::Result<DataFrame> {
+    let cc = g.get_cc_labels();
+    let mut n_components = vec![];
+    let mut coverage = vec![];
+    for ns in iter_roaring(nodes) {
+        let ns: RoaringBitmap = ns.try_into()?;
+        let touched: Vec<_> = ns
+            .iter()
+            .map(|u| cc.labels[u as usize] as usize)
+            .unique()
+            .collect();
+        let total: u64 = touched.iter().map(|&c| cc.num_nodes[c] as u64).sum();
+        n_components.push(touched.len() as u32);
+        coverage.push(if total == 0 {
+            0.0
+        } else {
+            ns.len() as f64 / total as f64
+        });
+    }
+    Ok(df!(
+        "n_components" => n_components,
+        "component_coverage" => coverage,
+    )?)
+}
+
+/// One row per connected component (`label` as in `get_cc_labels`) with its size `n`,
+/// the number of clusters touching it (`n_clusters`) and of its nodes in no cluster
+/// (`n_uncovered`).
+pub fn component_cluster_coverage(g: &Graph, nodes: &Series) -> anyhow::Result<DataFrame> {
+    let cc = g.get_cc_labels();
+    let k = cc.num_nodes.len();
+    let mut n_clusters = vec![0u32; k];
+    let mut covered = RoaringBitmap::new();
+    for ns in iter_roaring(nodes) {
+        let ns: RoaringBitmap = ns.try_into()?;
+        for c in ns.iter().map(|u| cc.labels[u as usize] as usize).unique() {
+            n_clusters[c] += 1;
+        }
+        covered |= ns;
+    }
+    let mut n_covered = vec![0u64; k];
+    for u in covered.iter() {
+        n_covered[cc.labels[u as usize] as usize] += 1;
+    }
+    let n = cc.num_nodes.iter().map(|&it| it as u64).collect_vec();
+    let n_uncovered = n.iter().zip(&n_covered).map(|(n, c)| n - c).collect_vec();
+    Ok(df!(
+        "label" => (0..k as u32).collect_vec(),
+        "n" => n,
+        "n_clusters" => n_clusters,
+        "n_uncovered" => n_uncovered,
+    )?)
+}
+
+/// PageRank aggregates of every cluster: its highest-ranked node (`core_node`, in
+/// original ids), that node's score (`max_pagerank`) and the total score of the cluster
+/// (`pagerank_mass`).
+pub fn centrality_statistics(
+    g: &Graph,
+    nodes: &Series,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> anyhow::Result<DataFrame> {
+    let raw = &g.data.graph;
+    let scores = centrality::pagerank(raw, damping, tolerance, max_iterations);
+    let mut core_node = vec![];
+    let mut max_pagerank = vec![];
+    let mut mass = vec![];
+    for ns in iter_roaring(nodes) {
+        let ns: RoaringBitmap = ns.try_into()?;
+        let core = ns
+            .iter()
+            .max_by(|&u, &v| scores[u as usize].total_cmp(&scores[v as usize]));
+        core_node.push(core.map(|u| raw.name_set.rev[u as usize] as u32));
+        max_pagerank.push(core.map(|u| scores[u as usize]));
+        mass.push(ns.iter().map(|u| scores[u as usize]).sum::<f64>());
+    }
+    Ok(df!(
+        "core_node" => core_node,
+        "max_pagerank" => max_pagerank,
+        "pagerank_mass" => mass,
+    )?)
+}
+
+/// The estimated betweenness summed over the boundary of every cluster, i.e. its nodes
+/// with a neighbor outside (`boundary_betweenness`): how much the cluster brokers
+/// shortest paths through its edge.
+pub fn betweenness_statistics(
+    g: &Graph,
+    nodes: &Series,
+    sampling: BetweennessSampling,
+    seed: u64,
+) -> anyhow::Result<DataFrame> {
+    let raw = &g.data.graph;
+    let scores = centrality::betweenness(raw, sampling, seed)?;
+    let mut boundary = vec![];
+    for ns in iter_roaring(nodes) {
+        let ns: RoaringBitmap = ns.try_into()?;
+        boundary.push(
+            ns.iter()
+                .filter(|&u| {
+                    raw.nodes[u as usize]
+                        .edges
+                        .iter()
+                        .any(|&v| !ns.contains(v as u32))
+                })
+                .map(|u| scores[u as usize])
+                .sum::<f64>(),
+        );
+    }
+    Ok(df!("boundary_betweenness" => boundary)?)
+}
+
+/// The nodes within `hops` hops of `nodes`, `nodes` included.
+fn expand(g: &aocluster::base::Graph, nodes: &RoaringBitmap, hops: u32) -> RoaringBitmap {
+    let mut reached = nodes.clone();
+    let mut frontier = nodes.clone();
+    for _ in 0..hops {
+        let mut next = RoaringBitmap::new();
+        for u in frontier.iter() {
+            for &v in &g.nodes[u as usize].edges {
+                if !reached.contains(v as u32) {
+                    next.insert(v as u32);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        reached |= &next;
+        frontier = next;
+    }
+    reached
+}
+
+/// Every node set of `series` grown by `hops` hops.
+pub fn nodeset_expand(g: &Graph, series: &Series, hops: u32) -> anyhow::Result<Series> {
+    let raw = &g.data.graph;
+    let sets = iter_roaring(series)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let expanded = sets
+        .into_par_iter()
+        .map(|nodes| expand(raw, &nodes, hops))
+        .collect();
+    Ok(build_series_from_bitmap(expanded))
+}
+
+/// The nodes outside every node set of `series` that are adjacent to it.
+pub fn nodeset_boundary(g: &Graph, series: &Series) -> anyhow::Result<Series> {
+    let raw = &g.data.graph;
+    let sets = iter_roaring(series)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let boundaries = sets
+        .into_par_iter()
+        .map(|nodes| expand(raw, &nodes, 1) - nodes)
+        .collect();
+    Ok(build_series_from_bitmap(boundaries))
+}
+
+/// The number of nodes adjacent to every node set of `series` but outside it.
+pub fn boundary_size(g: &Graph, series: &Series) -> anyhow::Result<Series> {
+    let raw = &g.data.graph;
+    let sets = iter_roaring(series)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let sizes: Vec<u64> = sets
+        .into_par_iter()
+        .map(|nodes| expand(raw, &nodes, 1).len() - nodes.len())
+        .collect();
+    Ok(Series::new("boundary_size", sizes))
+}
+
+/// Z-scores (`z_score`) and p-values (`p_value`) of the internal edge counts `m` of
+/// clusters of `n` nodes and volume `vol` under `null`, simulated from `samples` random
+/// graphs if given, otherwise by normal approximation.
+pub fn null_significance(
+    g: &Graph,
+    n: &Series,
+    m: &Series,
+    vol: &Series,
+    null: NullModel,
+    samples: Option<usize>,
+    seed: u64,
+) -> anyhow::Result<DataFrame> {
+    let (n, m, vol) = (
+        n.cast(&DataType::UInt64)?,
+        m.cast(&DataType::UInt64)?,
+        vol.cast(&DataType::UInt64)?,
+    );
+    let clusters = n
+        .u64()?
+        .into_iter()
+        .zip(m.u64()?)
+        .zip(vol.u64()?)
+        .map(|((n, m), vol)| match (n, m, vol) {
+            (Some(n), Some(m), Some(vol)) => Ok(ClusterSize { n, m, vol }),
+            _ => Err(anyhow::anyhow!("null cluster size")),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let (graph_n, graph_m) = (g.n() as u64, g.m());
+    let significance = match samples {
+        Some(samples) => null.simulate(graph_n, graph_m, &clusters, samples, seed)?,
+        None => null.analytic(graph_n, graph_m, &clusters),
+    };
+    Ok(df!(
+        "z_score" => significance.iter().map(|it| it.z_score).collect_vec(),
+        "p_value" => significance.iter().map(|it| it.p_value).collect_vec(),
+    )?)
+}
+
+pub fn nodeset_to_list(g: &Graph, series: &Series) -> anyhow::Result<Series> {
+    let mut ans = vec![];
+    let g = &g.data.graph;
+    for bm in iter_roaring(series) {
+        let bm: RoaringBitmap = bm.try_into()?;
+        let s = bm
+            .iter()
+            .map(|it| g.name_set.rev[it as usize] as u32)
+            .collect::<Series>();
+        ans.push(s);
+    }
+    Ok(Series::new("nodes_list", ans))
+}
+
+pub fn covered_edges(g: &Graph, series: &Series, progress: &ProgressSpec) -> Series {
+    let g = &g.data;
+    let progress = progress.start(Some(series.len() as u64), "collecting edges");
+    let nodesets = iter_roaring(series)
+        .map(|it| it.try_into().unwrap())
+        .map(|it| {
+            progress.inc(1);
+            edgeset(g, &it)
+        })
+        .map(EfficientSet::BigSet)
+        .collect::<Vec<_>>();
+    progress.finish();
+    build_series_from_sets(nodesets)
+}
+
+pub fn covered_edges_count(g: &Graph, series: &Series, progress: &ProgressSpec) -> u64 {
+    let g = &g.data;
+    let progress = progress.start(Some(series.len() as u64), "collecting edges");
+    let edgesets = iter_roaring(series)
+        .map(|it| it.try_into().unwrap())
+        .par_bridge()
+        .map(|it| {
+            progress.inc(1);
+            edgeset(g, &it)
+        })
+        .collect::<Vec<_>>();
+    progress.finish();
+    edgesets.union().len() as u64
+}
+
+pub fn edgeset_to_list(g: &Graph, series: &Series) -> anyhow::Result<Series> {
+    let mut ans = vec![];
+    let rev = &g.data.graph.name_set.rev;
+    for set in iter_roaring(series) {
+        let (src, dst): (Vec<u32>, Vec<u32>) = set
+            .iter()
+            .map(|e| {
+                let (u, v) = g.edge_endpoints(e);
+                (rev[u] as u32, rev[v] as u32)
+            })
+            .unzip();
+        let s = StructChunked::new("", &[Series::new("src", src), Series::new("dst", dst)])?;
+        ans.push(s.into_series());
+    }
+    if ans.is_empty() {
+        let fields = vec![
+            Field::new("src", DataType::UInt32),
+            Field::new("dst", DataType::UInt32),
+        ];
+        return Ok(Series::new_empty(
+            "edges_list",
+            &DataType::List(Box::new(DataType::Struct(fields))),
+        ));
+    }
+    Ok(Series::new("edges_list", ans))
+}
+
+pub fn popcnt(series: &Series) -> Series {
+    iter_roaring(series)
+        .map(|bitmap| bitmap.len() as u32)
+        .collect()
+}
+
+pub fn bitmap_union(series: &Series) -> Series {
+    let s = iter_roaring(series).collect::<Vec<EfficientSet>>();
+    build_series_from_sets(vec![s.union()])
+}
+
+fn edgeset(g: &EnrichedGraph, bm: &RoaringBitmap) -> RoaringTreemap {
+    let graph = &g.graph;
+    let acc = &g.acc_num_edges;
+    let tm = RoaringTreemap::from_sorted_iter(bm.iter().flat_map(|u| {
+        let edges = &graph.nodes[u as usize].edges;
+        let shift = acc[u as usize];
+        edges
+            .iter()
+            .filter(move |e| u < **e as u32)
+            .enumerate()
+            .filter_map(move |(offset, &v)| {
+                if bm.contains(v as u32) {
+                    Some(shift + offset as u64)
+                } else {
+                    None
+                }
+            })
+    }))
+    .unwrap();
+    tm
+}
+
+/// Edges of the quotient graph of a clustering as `(label_a, label_b, n_edges)`.
+/// Rows with equal labels count the internal edges. Nodes in several clusters are
+/// attributed to the first one (see `quotient::edge_counts`), so with overlapping
+/// clusters the internal counts can be below the `m` column.
+pub fn quotient_edges(g: &Graph, labels: &Series, nodes: &Series) -> anyhow::Result<DataFrame> {
+    let clusters = iter_roaring(nodes)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    quotient_frame(labels, quotient::edge_counts(&g.data.graph, &clusters))
+}
+
+fn quotient_frame(labels: &Series, rows: Vec<(u32, u32, u64)>) -> anyhow::Result<DataFrame> {
+    let (a, b, n_edges): (Vec<_>, Vec<_>, Vec<_>) = rows.into_iter().multiunzip();
+    let mut label_a = labels.take(&IdxCa::from_vec("label_a", a))?;
+    label_a.rename("label_a");
+    let mut label_b = labels.take(&IdxCa::from_vec("label_b", b))?;
+    label_b.rename("label_b");
+    Ok(df!(
+        "label_a" => label_a,
+        "label_b" => label_b,
+        "n_edges" => n_edges,
+    )?)
+}
+
+/// Collapses each cluster into a single node named by its (integer) label, and returns
+/// it with the edge multiplicities of `quotient_edges`, which the graph itself drops.
+pub fn quotient_graph(
+    g: &Graph,
+    labels: &Series,
+    nodes: &Series,
+) -> anyhow::Result<(Graph, DataFrame)> {
+    let names = labels
+        .cast(&DataType::UInt64)
+        .ok()
+        .filter(|it| it.null_count() == 0)
+        .ok_or_else(|| anyhow::anyhow!("quotient graphs need non-negative integer labels"))?;
+    let names = names
+        .u64()?
+        .into_no_null_iter()
+        .map(|it| it as usize)
+        .collect::<Vec<_>>();
+    anyhow::ensure!(
+        names.iter().all_unique(),
+        "quotient graphs need every label to be unique"
+    );
+    let clusters = iter_roaring(nodes)
+        .map(|it| it.try_into())
+        .collect::<Result<Vec<RoaringBitmap>, _>>()?;
+    let rows = quotient::edge_counts(&g.data.graph, &clusters);
+    let raw = builder::assemble_from_edges(
+        names,
+        rows.iter().map(|&(a, b, _)| (a as usize, b as usize)),
+    );
+    Ok((Graph::from_raw(raw), quotient_frame(labels, rows)?))
+}
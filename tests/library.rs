@@ -0,0 +1,659 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use belinda::{
+    compare::compare_memberships,
+    edgelist::{Policy, Symmetrize},
+    io::{convert_clustering, read_membership_table},
+    read_membership_file, read_membership_series, stats, ClusterFrame, Graph, GraphReadOptions,
+    ProgressSpec, SingletonMode,
+};
+use polars::prelude::Series;
+
+const GRAPH: &str = "resources/discont_graph.txt";
+const CLUSTERING: &str = "resources/discont_graph.clus.txt";
+
+/// A fresh path named `name`, in a directory of its own so that concurrent test runs
+/// do not collide.
+fn temp_path(name: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "belinda-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name).to_str().unwrap().to_owned()
+}
+
+/// Writes `edges` (an edge list) to a temporary file and reads it back.
+fn graph(edges: &str) -> Graph {
+    let path = temp_path("graph.tsv");
+    std::fs::write(&path, edges).unwrap();
+    Graph::open(&path).unwrap()
+}
+
+/// The clustering putting each of `nodes` in the cluster of the same position in `labels`.
+fn clustering(g: &Graph, nodes: &[u32], labels: &[u32]) -> ClusterFrame {
+    let nodes = Series::new("node", nodes);
+    let labels = Series::new("label", labels);
+    read_membership_series(
+        g,
+        &nodes,
+        &labels,
+        SingletonMode::AsIs,
+        &ProgressSpec::Hidden,
+    )
+    .unwrap()
+}
+
+#[test]
+fn graph_has_sane_information() {
+    let g = Graph::open(GRAPH).unwrap();
+    assert_eq!(g.n(), 6);
+    assert_eq!(g.m(), 5);
+    assert_eq!(g.num_components(), 1);
+}
+
+#[test]
+fn clustering_differences() {
+    let g = Graph::open(GRAPH).unwrap();
+    let read = |mode| {
+        read_membership_file(&g, CLUSTERING, b'\t', mode, false, &ProgressSpec::Hidden).unwrap()
+    };
+    assert_eq!(read(SingletonMode::Ignore).len(), 1);
+    assert_eq!(read(SingletonMode::AsIs).len(), 2);
+    assert_eq!(read(SingletonMode::AutoPopulate).len(), 4);
+}
+
+#[test]
+fn autopopulate_covers_all_nodes() {
+    let g = Graph::open(GRAPH).unwrap();
+    let clus = read_membership_file(
+        &g,
+        CLUSTERING,
+        b'\t',
+        SingletonMode::AutoPopulate,
+        false,
+        &ProgressSpec::Hidden,
+    )
+    .unwrap();
+    let sizes: u64 = clus.node_sets().map(|it| it.len()).sum();
+    assert_eq!(sizes, g.n() as u64);
+    let cover = stats::bitmap_union(clus.nodes());
+    assert_eq!(stats::popcnt(&cover).u32().unwrap().get(0), Some(g.n()));
+}
+
+#[test]
+fn leiden_covers_all_nodes() {
+    let g = Graph::open(GRAPH).unwrap();
+    let config = belinda::leiden::LeidenConfig {
+        objective: belinda::leiden::Objective::Modularity,
+        resolution: 1.0,
+        seed: 1,
+        iterations: 2,
+    };
+    let clus = g.leiden(&config, None).unwrap();
+    let n: u64 = clus.node_sets().map(|it| it.len()).sum();
+    assert_eq!(n, g.n() as u64);
+    assert_eq!(clus.membership(&g).unwrap().len(), g.n() as usize);
+}
+
+#[test]
+fn leiden_optimizes_the_cpm_and_modularity_expressions() {
+    use belinda::leiden::{LeidenConfig, Objective};
+    // triangles 1 2 3 and 4 5 6 joined by the edge 3 4
+    let g = graph("1 2\n2 3\n3 1\n4 5\n5 6\n6 4\n3 4\n");
+    let column = |clus: &ClusterFrame, name| {
+        let values = clus.column(name).unwrap().u64().unwrap();
+        values
+            .into_no_null_iter()
+            .map(|it| it as f64)
+            .collect::<Vec<_>>()
+    };
+    // `g.cpm(r)` and `g.modularity(r)` of `exprs.py`, summed over the clusters
+    let cpm = |clus: &ClusterFrame, r: f64| {
+        let (n, m) = (column(clus, "n"), column(clus, "m"));
+        (0..n.len())
+            .map(|i| m[i] - r * n[i] * (n[i] - 1.0) / 2.0)
+            .sum::<f64>()
+    };
+    let modularity = |clus: &ClusterFrame| {
+        let (m, c, big_l) = (column(clus, "m"), column(clus, "c"), g.m() as f64);
+        (0..m.len())
+            .map(|i| m[i] / big_l - ((2.0 * m[i] + c[i]) / (2.0 * big_l)).powi(2))
+            .sum::<f64>()
+    };
+    let config = |objective, resolution| LeidenConfig {
+        objective,
+        resolution,
+        seed: 0,
+        iterations: 2,
+    };
+    let triangles = clustering(&g, &[1, 2, 3, 4, 5, 6], &[0, 0, 0, 1, 1, 1]);
+
+    let clus = g.leiden(&config(Objective::Cpm, 0.5), None).unwrap();
+    assert_eq!(clus.len(), 2);
+    assert!((cpm(&clus, 0.5) - cpm(&triangles, 0.5)).abs() < 1e-12);
+    let clus = g.leiden(&config(Objective::Modularity, 1.0), None).unwrap();
+    assert_eq!(clus.len(), 2);
+    assert!((modularity(&clus) - modularity(&triangles)).abs() < 1e-12);
+    assert!((modularity(&triangles) - (6.0 / 7.0 - 0.5)).abs() < 1e-12);
+
+    // every row claims the same nodes, so only the first one labels them
+    let overlapping = clustering(&g, &[1, 2, 1, 2, 1, 2], &[0, 0, 1, 1, 2, 2]);
+    let initial = overlapping.membership(&g).unwrap();
+    let mut labels = initial.clone();
+    labels.sort_unstable();
+    assert_eq!(labels, vec![0, 0, 1, 2, 3, 4]);
+    let clus = g
+        .leiden(&config(Objective::Cpm, 0.5), Some(initial))
+        .unwrap();
+    assert!((cpm(&clus, 0.5) - cpm(&triangles, 0.5)).abs() < 1e-12);
+}
+
+#[test]
+fn quotient_edges_partition_clustered_edges() {
+    // triangles 1 2 3 and 4 5 6 joined by the edge 3 4
+    let g = graph("1 2\n2 3\n3 1\n4 5\n5 6\n6 4\n3 4\n");
+    let clus = clustering(&g, &[1, 2, 3, 4, 5, 6], &[10, 10, 10, 20, 20, 20]);
+    let (q, edges) = stats::quotient_graph(&g, clus.labels(), clus.nodes()).unwrap();
+    assert_eq!((q.n(), q.m()), (2, 1));
+    assert_eq!(q.shortest_path(10, 20).unwrap(), Some(vec![10, 20]));
+    let n_edges = edges.column("n_edges").unwrap().u64().unwrap();
+    assert_eq!(
+        n_edges.into_no_null_iter().collect::<Vec<_>>(),
+        vec![3, 1, 3]
+    );
+    // the clustering is disjoint, so the internal counts are its `m` column
+    let m = clus.column("m").unwrap().u64().unwrap();
+    assert_eq!((n_edges.get(0), n_edges.get(2)), (m.get(0), m.get(1)));
+
+    // node 4 is in both clusters, but its edges are counted once
+    let overlapping = clustering(&g, &[1, 2, 3, 4, 4, 5, 6], &[0, 0, 0, 0, 1, 1, 1]);
+    let edges = stats::quotient_edges(&g, overlapping.labels(), overlapping.nodes()).unwrap();
+    let n_edges = edges.column("n_edges").unwrap();
+    assert_eq!(n_edges.sum::<u64>(), Some(g.m()));
+}
+
+#[test]
+fn edge_table_annotates_clusters() {
+    let g = Graph::open(GRAPH).unwrap();
+    let clus = clustering(&g, &[0, 1, 2, 3, 4], &[7, 7, 7, 8, 8]);
+    let df = g.edges_df(Some((clus.labels(), clus.nodes()))).unwrap();
+    assert_eq!(df.height(), 5);
+    let intra = df.column("intra").unwrap().bool().unwrap();
+    assert_eq!(intra.into_no_null_iter().filter(|&it| it).count(), 2);
+    // only the leaf 99 is unclustered
+    let missing = |name| df.column(name).unwrap().null_count();
+    assert_eq!(missing("src_label") + missing("dst_label"), 1);
+}
+
+#[test]
+fn edge_set_lookups_and_decoding() {
+    let g = Graph::open(GRAPH).unwrap();
+    let clus = clustering(&g, &[0, 1, 2, 3, 4], &[7, 7, 7, 8, 8]);
+    let df = g.edges_df(None).unwrap();
+    let src = Series::new("src", [1u32, 1, 5]);
+    let dst = Series::new("dst", [0u32, 2, 0]);
+    let ids = g.edge_ids_of(&src, &dst).unwrap();
+    assert_eq!(&ids[1..], &[None, None]);
+    let row = (0..df.height())
+        .find(|&row| {
+            let endpoint = |name| df.column(name).unwrap().u32().unwrap().get(row).unwrap();
+            endpoint("src").max(endpoint("dst")) == 1
+        })
+        .unwrap();
+    let edge_id = df.column("edge_id").unwrap().u64().unwrap().get(row);
+    assert_eq!(ids[0], edge_id);
+
+    let sets = stats::covered_edges(&g, clus.nodes(), &ProgressSpec::Hidden);
+    let lists = stats::edgeset_to_list(&g, &sets).unwrap();
+    let mut pairs = vec![];
+    for list in lists.list().unwrap().into_no_null_iter() {
+        let endpoints = list.struct_().unwrap();
+        let src = endpoints.field_by_name("src").unwrap();
+        let dst = endpoints.field_by_name("dst").unwrap();
+        let (src, dst) = (src.u32().unwrap(), dst.u32().unwrap());
+        for (u, v) in src.into_no_null_iter().zip(dst.into_no_null_iter()) {
+            pairs.push((u.min(v), u.max(v)));
+        }
+    }
+    pairs.sort_unstable();
+    assert_eq!(pairs, vec![(0, 1), (0, 2)]);
+}
+
+#[test]
+fn label_propagation_finds_disjoint_triangles() {
+    let g = graph("1 2\n2 3\n3 1\n4 5\n5 6\n6 4\n");
+    let (clus, report) = g.label_propagation(3, 100).unwrap();
+    assert!(report.converged);
+    assert_eq!(report.changed, 0);
+    assert_eq!(clus.len(), 2);
+    let m = clus.column("m").unwrap().u64().unwrap();
+    assert_eq!(m.into_no_null_iter().collect::<Vec<_>>(), vec![3, 3]);
+    let (again, _) = g.label_propagation(3, 100).unwrap();
+    assert_eq!(clus.membership(&g).unwrap(), again.membership(&g).unwrap());
+}
+
+#[test]
+fn identical_memberships_agree() {
+    let a = read_membership_table(CLUSTERING, b'\t', false).unwrap();
+    let cmp = compare_memberships(&a, &a).unwrap();
+    assert_eq!(cmp.clusters_a, 2);
+    assert_eq!(cmp.shared_nodes, 4);
+    assert!((cmp.nmi - 1.0).abs() < 1e-9);
+    assert!((cmp.ari - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn membership_json_round_trip() {
+    let (json, tsv) = (temp_path("round-trip.json"), temp_path("round-trip.tsv"));
+    let (json, tsv) = (json.as_str(), tsv.as_str());
+    convert_clustering(CLUSTERING, json, b'\t', false).unwrap();
+    convert_clustering(json, tsv, b'\t', false).unwrap();
+    let a = read_membership_table(CLUSTERING, b'\t', false).unwrap();
+    let b = read_membership_table(tsv, b'\t', false).unwrap();
+    let cmp = compare_memberships(&a, &b).unwrap();
+    assert_eq!(cmp.shared_nodes, 4);
+    assert!((cmp.ari - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn reads_compressed_graphs() {
+    let g = Graph::open(GRAPH).unwrap();
+    for ext in ["gz", "zst", "bz2"] {
+        let path = temp_path(&format!("compressed.tsv.{}", ext));
+        g.write_edgelist(&path).unwrap();
+        let h = Graph::open(&path).unwrap();
+        assert_eq!((h.n(), h.m()), (g.n(), g.m()));
+    }
+}
+
+#[test]
+fn compressed_clusterings_round_trip() {
+    let a = read_membership_table(CLUSTERING, b'\t', false).unwrap();
+    for ext in ["gz", "zst", "bz2"] {
+        let json = temp_path(&format!("clus.json.{}", ext));
+        let tsv = temp_path(&format!("clus.tsv.{}", ext));
+        convert_clustering(CLUSTERING, &json, b'\t', false).unwrap();
+        convert_clustering(&json, &tsv, b'\t', false).unwrap();
+        let b = read_membership_table(&tsv, b'\t', false).unwrap();
+        assert_eq!(b.height(), a.height());
+        assert!((compare_memberships(&a, &b).unwrap().ari - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn read_options_report_skipped_lines() {
+    let path = temp_path("options.csv");
+    std::fs::write(
+        &path,
+        "# snap-style comment\nsrc,dst,weight\n1,2,0.5\n2,1,0.5\n1,2,1\n3,3,1\n\n2,3,1\n",
+    )
+    .unwrap();
+    let path = path.as_str();
+    let options = GraphReadOptions {
+        delimiter: Some(','),
+        skip_header: true,
+        ..Default::default()
+    };
+    let g = Graph::open_with(path, &options).unwrap();
+    assert_eq!((g.n(), g.m()), (3, 2));
+    let report = g.read_report().unwrap();
+    assert_eq!(report.lines, 8);
+    assert_eq!((report.comments, report.header, report.blank), (1, 1, 1));
+    // `2,1` repeats `1,2` in reverse
+    assert_eq!((report.self_loops, report.duplicates), (1, 2));
+
+    let mutual = Graph::open_with(
+        path,
+        &GraphReadOptions {
+            symmetrize: Symmetrize::Mutual,
+            ..options.clone()
+        },
+    )
+    .unwrap();
+    assert_eq!(mutual.m(), 1);
+    assert_eq!(mutual.read_report().unwrap().unreciprocated, 1);
+
+    let strict = GraphReadOptions {
+        duplicates: Policy::Error,
+        ..options
+    };
+    assert!(Graph::open_with(path, &strict).is_err());
+
+    let reversed = temp_path("reversed.tsv");
+    std::fs::write(&reversed, "1 2\n2 3\n3 2\n").unwrap();
+    let g = Graph::open(&reversed).unwrap();
+    assert_eq!((g.m(), g.read_report().unwrap().duplicates), (2, 1));
+    let strict = GraphReadOptions {
+        duplicates: Policy::Error,
+        ..Default::default()
+    };
+    assert!(Graph::open_with(&reversed, &strict).is_err());
+    let arcs = GraphReadOptions {
+        directed: true,
+        ..strict
+    };
+    let g = Graph::open_with(&reversed, &arcs).unwrap();
+    assert_eq!(g.directed().unwrap().m(), 3);
+}
+
+#[test]
+fn directed_components_and_cluster_stats() {
+    let path = temp_path("directed.tsv");
+    // a 3-cycle 1 -> 2 -> 3 -> 1 feeding into 4 -> 5 -> 4
+    std::fs::write(&path, "1 2\n2 3\n3 1\n3 4\n4 5\n5 4\n").unwrap();
+    let options = GraphReadOptions {
+        directed: true,
+        ..Default::default()
+    };
+    let g = Graph::open_with(&path, &options).unwrap();
+    assert_eq!(g.directed().unwrap().m(), 6);
+    assert_eq!(g.m(), 5);
+    assert_eq!(g.get_weak_cc_labels().unwrap().sizes, vec![5]);
+    let mut strong = g.get_strong_cc_labels().unwrap().sizes.clone();
+    strong.sort_unstable();
+    assert_eq!(strong, vec![2, 3]);
+
+    // only 4 <-> 5 is reciprocated, but the directed graph keeps every arc
+    let mutual = GraphReadOptions {
+        symmetrize: Symmetrize::Mutual,
+        ..options.clone()
+    };
+    let h = Graph::open_with(&path, &mutual).unwrap();
+    assert_eq!((h.m(), h.directed().unwrap().m()), (1, 6));
+    assert_eq!(h.get_strong_cc_labels().unwrap().sizes.len(), 2);
+
+    let clus = clustering(&g, &[1, 2, 3], &[0, 0, 0]);
+    let df = stats::directed_cluster_stats(&g, clus.nodes(), 1.0).unwrap();
+    let column = |name| df.column(name).unwrap().u64().unwrap().get(0).unwrap();
+    assert_eq!(
+        (column("m_arcs"), column("out_cut"), column("in_cut")),
+        (3, 1, 0)
+    );
+}
+
+#[test]
+fn biconnected_components_bridges_and_cut_statistics() {
+    // triangles 1 2 3 and 3 4 5 sharing node 3, with a pendant edge 5 6
+    let g = graph("1 2\n2 3\n3 1\n3 4\n4 5\n5 3\n5 6\n");
+    let bicc = g.get_biconnectivity();
+    assert_eq!(bicc.num_components, 3);
+    assert_eq!(bicc.bridges.len(), 1);
+    let mut points = g.articulation_points();
+    points.sort_unstable();
+    assert_eq!(points, vec![3, 5]);
+    let bridges = g.biconnected_df(true).unwrap();
+    let endpoint = |name| bridges.column(name).unwrap().u32().unwrap().get(0).unwrap();
+    assert_eq!((endpoint("src"), endpoint("dst")), (5, 6));
+
+    let clus = clustering(&g, &[1, 2, 3, 4, 5, 6], &[0, 0, 0, 0, 0, 1]);
+    let df = stats::cut_statistics(&g, clus.nodes()).unwrap();
+    let points = df.column("n_articulation_points").unwrap().u32().unwrap();
+    let bridges = df.column("n_bridges").unwrap().u64().unwrap();
+    assert_eq!((points.get(0), bridges.get(0)), (Some(1), Some(0)));
+}
+
+#[test]
+fn components_frame_bounds_diameters() {
+    // a path 1 - 2 - 3 - 4, a triangle 5 6 7 and a square 8 9 10 11
+    let g = graph("1 2\n2 3\n3 4\n5 6\n6 7\n7 5\n8 9\n9 10\n10 11\n11 8\n");
+    let components = g.components().unwrap();
+    assert_eq!(components.len(), 3);
+    let df = components.df();
+    let column = |name| {
+        let column = df.column(name).unwrap().u32().unwrap();
+        column.into_no_null_iter().collect::<Vec<_>>()
+    };
+    // exact on the tree and the clique
+    assert_eq!(column("diameter_lower"), vec![3, 1, 2]);
+    assert_eq!(column("diameter_upper"), vec![3, 1, 4]);
+    let m = df.column("m").unwrap().u64().unwrap();
+    assert_eq!((m.get(0), m.get(1)), (Some(3), Some(3)));
+}
+
+#[test]
+fn cluster_component_coverage() {
+    // components {1, 2, 3, 4} and {5, 6}
+    let g = graph("1 2\n2 3\n3 4\n5 6\n");
+    let clus = clustering(&g, &[1, 2, 4, 5], &[0, 0, 1, 1]);
+    let df = stats::cluster_component_coverage(&g, clus.nodes()).unwrap();
+    let touched = df.column("n_components").unwrap().u32().unwrap();
+    let coverage = df.column("component_coverage").unwrap().f64().unwrap();
+    assert_eq!((touched.get(0), coverage.get(0)), (Some(1), Some(0.5)));
+    assert_eq!(
+        (touched.get(1), coverage.get(1)),
+        (Some(2), Some(1.0 / 3.0))
+    );
+
+    let df = stats::component_cluster_coverage(&g, clus.nodes()).unwrap();
+    let clusters = df.column("n_clusters").unwrap().u32().unwrap();
+    let uncovered = df.column("n_uncovered").unwrap().u64().unwrap();
+    assert_eq!((clusters.get(0), uncovered.get(0)), (Some(2), Some(1)));
+    assert_eq!((clusters.get(1), uncovered.get(1)), (Some(1), Some(1)));
+}
+
+#[test]
+fn centrality_columns_and_cluster_cores() {
+    let g = Graph::open(GRAPH).unwrap();
+    let columns = belinda::CentralityColumns {
+        pagerank: Some(0.85),
+        eigenvector: true,
+        k_hop: Some(2),
+        ..Default::default()
+    };
+    let df = g.nodes_df(None, false, &columns).unwrap();
+    let pagerank = df.column("pagerank").unwrap().f64().unwrap();
+    assert!((pagerank.sum().unwrap() - 1.0).abs() < 1e-6);
+    let node = df.column("node").unwrap().u32().unwrap();
+    let hub = |name| {
+        let scores = df.column(name).unwrap().f64().unwrap();
+        let best = (0..df.height())
+            .max_by(|&a, &b| scores.get(a).unwrap().total_cmp(&scores.get(b).unwrap()));
+        node.get(best.unwrap()).unwrap()
+    };
+    assert_eq!(hub("pagerank"), 0);
+    assert_eq!(hub("eigenvector"), 0);
+    let reach = df.column("k_hop_degree").unwrap().u32().unwrap();
+    assert!(reach.into_no_null_iter().all(|it| it == 5));
+
+    let clus = clustering(&g, &[0, 1, 2, 3, 4, 99], &[0; 6]);
+    let df = stats::centrality_statistics(&g, clus.nodes(), 0.85, 1e-9, 100).unwrap();
+    assert_eq!(
+        df.column("core_node").unwrap().u32().unwrap().get(0),
+        Some(0)
+    );
+    let mass = df
+        .column("pagerank_mass")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .get(0)
+        .unwrap();
+    assert!((mass - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn sampled_betweenness_finds_bridging_nodes() {
+    use belinda::centrality::{betweenness, BetweennessSampling};
+    let g = Graph::open(GRAPH).unwrap();
+    // in the star around node 0, every pair of leaves goes through the hub
+    let exact = betweenness(&g.raw(), BetweennessSampling::Sources(100), 0).unwrap();
+    let hub = g.raw().retrieve(0).unwrap();
+    assert!((exact[hub] - 10.0).abs() < 1e-9);
+    assert_eq!(exact.iter().filter(|&&it| it > 0.0).count(), 1);
+    let bound = BetweennessSampling::ErrorBound {
+        epsilon: 0.05,
+        delta: 0.1,
+    };
+    let estimate = betweenness(&g.raw(), bound, 7).unwrap();
+    // normalized over the 15 unordered pairs, and comfortably within the bound
+    assert!((estimate[hub] / 15.0 - 10.0 / 15.0).abs() < 0.1);
+    for invalid in [
+        BetweennessSampling::Sources(0),
+        BetweennessSampling::ErrorBound {
+            epsilon: 0.0,
+            delta: 0.1,
+        },
+        BetweennessSampling::ErrorBound {
+            epsilon: 0.05,
+            delta: 1.0,
+        },
+    ] {
+        assert!(betweenness(&g.raw(), invalid, 0).is_err());
+    }
+
+    let clus = clustering(&g, &[0, 1, 2, 3, 4, 99], &[0, 0, 0, 1, 1, 1]);
+    let df = stats::betweenness_statistics(&g, clus.nodes(), BetweennessSampling::Sources(6), 0)
+        .unwrap();
+    let boundary = df.column("boundary_betweenness").unwrap().f64().unwrap();
+    assert!((boundary.get(0).unwrap() - 10.0).abs() < 1e-9);
+    assert_eq!(boundary.get(1), Some(0.0));
+}
+
+#[test]
+fn traversals_bfs_shortest_path_and_ego() {
+    let g = Graph::open(GRAPH).unwrap();
+    let df = g.bfs(1, Some(1)).unwrap();
+    let distance = df.column("distance").unwrap().u32().unwrap();
+    assert_eq!(distance.into_no_null_iter().collect::<Vec<_>>(), vec![0, 1]);
+    assert_eq!(g.bfs(1, None).unwrap().height(), 6);
+    assert!(g.bfs(12345, None).is_err());
+
+    assert_eq!(g.shortest_path(1, 99).unwrap(), Some(vec![1, 0, 99]));
+    assert_eq!(g.shortest_path(4, 4).unwrap(), Some(vec![4]));
+
+    assert_eq!(g.ego(&[1], 0).unwrap().len(), 1);
+    assert_eq!(g.ego(&[1], 1).unwrap().len(), 2);
+    assert_eq!(g.ego(&[1, 2], 1).unwrap().len(), 3);
+    assert_eq!(g.ego(&[1], 2).unwrap().len(), 6);
+}
+
+#[test]
+fn boundaries_and_expansions_of_node_sets() {
+    let g = Graph::open(GRAPH).unwrap();
+    let clus = clustering(&g, &[1, 0, 2], &[0, 1, 1]);
+    // {1} borders the hub, {0, 2} borders every other leaf
+    let sizes = stats::boundary_size(&g, clus.nodes()).unwrap();
+    assert_eq!(
+        sizes.u64().unwrap().into_no_null_iter().collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    let counts = |sets: Series| {
+        let counts = stats::popcnt(&sets);
+        counts
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>()
+    };
+    let boundary = stats::nodeset_boundary(&g, clus.nodes()).unwrap();
+    assert_eq!(counts(boundary), vec![1, 3]);
+    let expanded = stats::nodeset_expand(&g, clus.nodes(), 2).unwrap();
+    assert_eq!(counts(expanded), vec![6, 6]);
+}
+
+#[test]
+fn yang_leskovec_quality_measures() {
+    let g = Graph::open(GRAPH).unwrap();
+    let clus = clustering(&g, &[0, 1, 2, 3, 4, 99], &[0, 0, 0, 1, 1, 1]);
+    let df = stats::quality_statistics(&g, clus.nodes(), &ProgressSpec::Hidden).unwrap();
+    let value = |name, row| df.column(name).unwrap().f64().unwrap().get(row).unwrap();
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+    // the hub with two leaves: 2 internal edges, 3 cut edges, all from the hub
+    assert!(close(value("internal_density", 0), 2.0 / 3.0));
+    assert!(close(value("expansion", 0), 1.0));
+    assert!(close(value("cut_ratio", 0), 1.0 / 3.0));
+    assert!(close(value("normalized_cut", 0), 3.0 / 7.0 + 3.0 / 9.0));
+    assert!(close(value("separability", 0), 2.0 / 3.0));
+    assert!(close(value("fomd", 0), 1.0 / 3.0));
+    assert!(close(value("tpr", 0), 0.0));
+    assert!(close(value("max_odf", 0), 0.6));
+    assert!(close(value("avg_odf", 0), 0.2));
+    assert!(close(value("flake_odf", 0), 1.0 / 3.0));
+    // three leaves without the hub
+    assert!(close(value("internal_density", 1), 0.0));
+    assert!(close(value("max_odf", 1), 1.0));
+    assert!(close(value("flake_odf", 1), 1.0));
+}
+
+#[test]
+fn null_model_significance() {
+    use belinda::null_model::NullModel;
+    // triangles 1 2 3 and 4 5 6 joined by the edge 3 4
+    let g = graph("1 2\n2 3\n3 1\n4 5\n5 6\n6 4\n3 4\n");
+    let (n, m, vol) = (
+        Series::new("n", [3u64]),
+        Series::new("m", [3u64]),
+        Series::new("vol", [7u64]),
+    );
+    let significance = |null, samples, seed| {
+        stats::null_significance(&g, &n, &m, &vol, null, samples, seed).unwrap()
+    };
+    let value = |df: &polars::prelude::DataFrame, name| {
+        df.column(name).unwrap().f64().unwrap().get(0).unwrap()
+    };
+    // 7 of 15 pairs are edges: 1.4 internal edges expected, with variance 0.64
+    let er = significance(NullModel::ErdosRenyi, None, 0);
+    assert!((value(&er, "z_score") - 2.0).abs() < 1e-9);
+    // the configuration model expects 7 (7 / 14)^2 = 1.75 internal edges
+    let configuration = significance(NullModel::Configuration, None, 0);
+    assert!((value(&configuration, "z_score") - 1.25 / 1.3125f64.sqrt()).abs() < 1e-9);
+    assert!(value(&configuration, "p_value") < 0.5);
+
+    // the exact p-value under G(N, M) is C(12, 4) / C(15, 7) = 0.077
+    let simulated = significance(NullModel::ErdosRenyi, Some(4000), 1);
+    assert!((value(&simulated, "p_value") - 495.0 / 6435.0).abs() < 0.02);
+    let again = significance(NullModel::ErdosRenyi, Some(4000), 1);
+    assert_eq!(value(&simulated, "p_value"), value(&again, "p_value"));
+    let stubs = significance(NullModel::Configuration, Some(1000), 1);
+    assert!(value(&stubs, "z_score") > 0.0);
+}
+
+#[test]
+fn random_graph_generators() {
+    use belinda::generators::{self, LfrConfig};
+    let total =
+        |df: &polars::prelude::DataFrame, name| df.column(name).unwrap().sum::<u64>().unwrap();
+    let complete = generators::erdos_renyi(20, 1.0, 0).unwrap();
+    assert_eq!((complete.n(), complete.m()), (20, 190));
+    let sparse = |seed| generators::erdos_renyi(200, 0.05, seed).unwrap().m();
+    assert_eq!(sparse(3), sparse(3));
+    assert!((700..1300).contains(&sparse(3)));
+
+    let cycle = generators::configuration_model(&[2; 50], 1).unwrap();
+    assert_eq!(cycle.n(), 50);
+    assert!(cycle.m() <= 50);
+    assert!(generators::configuration_model(&[1, 2], 1).is_err());
+
+    // disjoint cliques of 3, 4 and 5 nodes
+    let probabilities = vec![
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ];
+    let (g, blocks) = generators::stochastic_block_model(&[3, 4, 5], &probabilities, 0).unwrap();
+    assert_eq!((g.n(), g.m(), g.num_components()), (12, 19, 3));
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(total(blocks.df(), "m"), 19);
+
+    // without mixing, every edge stays inside its community
+    let config = LfrConfig {
+        n: 500,
+        mixing: 0.0,
+        seed: 7,
+        ..Default::default()
+    };
+    let (g, communities) = generators::lfr(&config).unwrap();
+    assert_eq!(g.n(), 500);
+    assert_eq!(total(communities.df(), "n"), 500);
+    assert_eq!(total(communities.df(), "m"), g.m());
+    let (g, communities) = generators::lfr(&LfrConfig {
+        mixing: 0.3,
+        ..config
+    })
+    .unwrap();
+    let internal = total(communities.df(), "m") as f64 / g.m() as f64;
+    assert!((0.5..0.9).contains(&internal));
+}
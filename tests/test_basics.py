@@ -1,4 +1,5 @@
 from belinda import *
+import logging
 import numpy as np
 import pytest
 
@@ -26,4 +27,177 @@ def test_autopopulate(simple_graph):
     cluster_sizes = c3.with_column(pl.col('nodes').set.popcnt().alias('cluster_size'))["cluster_size"].to_numpy()
     assert csize == simple_graph.n
     assert csize == sum_size
-    assert np.all(cluster_sizes >= 1)
\ No newline at end of file
+    assert np.all(cluster_sizes >= 1)
+
+def test_leiden_covers_all_nodes(simple_graph):
+    c = cluster_leiden(simple_graph, "modularity", seed=1)
+    assert c["n"].sum() == simple_graph.n
+    assert c["m"].sum() + c["c"].sum() // 2 == simple_graph.m
+
+
+def test_label_propagation_reports_convergence(simple_graph):
+    c = cluster_label_propagation(simple_graph, seed=1)
+    assert c["lpa_converged"].all()
+    assert c["lpa_iterations"].max() <= 100
+    assert c["lpa_iterations"].n_unique() == 1
+    assert c["n"].sum() == simple_graph.n
+    assert c["m"].sum() + c["c"].sum() // 2 == simple_graph.m
+
+
+def test_quotient_graph_is_named_by_label(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt")
+    edges = quotient_edges(simple_graph, c)
+    assert edges["n_edges"].sum() <= simple_graph.m
+    internal = edges.filter(pl.col("label_a") == pl.col("label_b"))
+    m = dict(zip(c["label"], c["m"]))
+    assert all(k == m[a] for a, k in zip(internal["label_a"], internal["n_edges"]))
+    assert internal["n_edges"].sum() == c["m"].sum()
+    q, weighted = quotient_graph(simple_graph, c)
+    assert q.n == len(c)
+    assert weighted.frame_equal(edges)
+
+
+def test_subgraph_of_cluster(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt", mode=SingletonMode.Ignore)
+    sub = simple_graph.subgraph(c["nodes"][0])
+    assert sub.n == c["n"][0]
+    assert sub.m == c["m"][0]
+
+
+def test_edges_annotate_clusters(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt", mode=SingletonMode.Ignore)
+    edges = simple_graph.edges(c)
+    assert len(edges) == simple_graph.m
+    assert edges["intra"].sum() == c["m"].sum()
+
+
+def test_edge_ids_and_edge_sets(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt", mode=SingletonMode.Ignore)
+    edges = simple_graph.edges()
+    ids = simple_graph.edge_ids(pl.Series([0, 1]), pl.Series([1, 2]))
+    assert ids.null_count() == 1
+    pairs = c.select(simple_graph.intra_edges(pl.col("nodes")).set.flatten_edges(simple_graph))
+    assert len(pairs.to_series().explode()) == c["m"].sum()
+    edge_set = simple_graph.edge_ids(edges["src"], edges["dst"], as_set=True)
+    assert popcnt(edge_set)[0] == simple_graph.m
+
+
+def test_threads_context_restores_pool():
+    before = get_nthreads()
+    with threads(1):
+        assert get_nthreads() == 1
+    assert get_nthreads() == before
+
+
+def test_threads_context_is_per_thread():
+    import threading
+    barrier = threading.Barrier(2)
+    seen = {}
+
+    def worker(n):
+        with threads(n):
+            barrier.wait()
+            seen[n] = get_nthreads()
+            barrier.wait()
+
+    workers = [threading.Thread(target=worker, args=(n,)) for n in (1, 2)]
+    for w in workers:
+        w.start()
+    for w in workers:
+        w.join()
+    assert seen == {1: 1, 2: 2}
+
+
+def test_components_are_a_clustering(simple_graph):
+    c = simple_graph.components()
+    assert len(c) == simple_graph.num_components()
+    assert c["n"].sum() == simple_graph.n
+    assert c["m"].sum() == simple_graph.m
+    assert (c["diameter_lower"] <= c["diameter_upper"]).all()
+
+
+def test_ego_composes_with_sets(simple_graph):
+    ego = simple_graph.ego([1, 2], radius=1)
+    assert simple_graph.subgraph(ego).n == 3
+    assert simple_graph.subgraph(ego).m == 2
+    assert simple_graph.shortest_path(1, 99) == [1, 0, 99]
+    assert len(simple_graph.bfs(0, max_depth=1)) == 6
+
+
+def test_boundary_and_expand(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt", mode=SingletonMode.Ignore)
+    c = c.with_columns([
+        simple_graph.boundary_size(),
+        pl.col("nodes").set.boundary(simple_graph).set.popcnt().alias("boundary_popcnt"),
+        pl.col("nodes").set.expand(simple_graph, 1).set.popcnt().alias("expanded"),
+    ])
+    assert (c["boundary_size"] == c["boundary_popcnt"]).all()
+    assert (c["expanded"] == c["n"] + c["boundary_size"]).all()
+
+
+def test_generators_plant_clusterings():
+    assert erdos_renyi(10, 1.0).m == 45
+    g, truth = stochastic_block_model([3, 4], [[1.0, 0.0], [0.0, 1.0]], seed=1)
+    assert (g.n, g.m) == (7, 9)
+    assert list(truth["n"]) == [3, 4]
+    g, truth = lfr(300, mixing=0.0, seed=2)
+    assert truth["n"].sum() == 300
+    assert truth["m"].sum() == g.m
+
+
+def test_quality_statistics(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt", mode=SingletonMode.Ignore)
+    q = quality_statistics(simple_graph, c)
+    assert q.columns[: len(c.columns)] == c.columns
+    # the hub with two of its leaves: 2 internal edges, 3 cut edges
+    assert q["internal_density"][0] == pytest.approx(2 / 3)
+    assert q["expansion"][0] == pytest.approx(1.0)
+    assert q["separability"][0] == pytest.approx(2 / 3)
+    assert q["flake_odf"][0] == pytest.approx(1 / 3)
+
+
+def test_null_significance_expressions(simple_graph):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt")
+    both = c.with_columns(simple_graph.significance(null="er")).unnest("significance")
+    z = c.with_columns(simple_graph.z_score(null="er"))["z_score"]
+    assert both["z_score"].to_list() == z.to_list()
+    simulated = c.with_columns(simple_graph.significance(samples=200, seed=1))
+    p_values = simulated.unnest("significance")["p_value"]
+    assert ((p_values > 0) & (p_values <= 1)).all()
+
+
+def test_directed_graph_keeps_unreciprocated_arcs(tmp_path):
+    path = tmp_path / "directed.tsv"
+    # a 3-cycle 1 -> 2 -> 3 -> 1 feeding into 4 -> 5 -> 4
+    path.write_text("1 2\n2 3\n3 1\n3 4\n4 5\n5 4\n")
+    g = Graph(str(path), directed=True)
+    assert (g.m, g.m_arcs) == (5, 6)
+    assert (g.num_weak_components(), g.num_strong_components()) == (1, 2)
+    assert g.largest_strong_component() == 3
+    mutual = Graph(str(path), directed=True, symmetrize="mutual")
+    assert (mutual.m, mutual.m_arcs) == (1, 6)
+    assert mutual.num_strong_components() == 2
+
+
+@pytest.mark.parametrize("ext", ["", ".gz", ".zst", ".bz2"])
+def test_compressed_writers_round_trip(simple_graph, tmp_path, ext):
+    c = read_membership(simple_graph, "resources/discont_graph.clus.txt", mode=SingletonMode.Ignore)
+    write_membership(simple_graph, c, tmp_path / f"clus.tsv{ext}")
+    members = read_membership(simple_graph, str(tmp_path / f"clus.tsv{ext}"), mode=SingletonMode.Ignore)
+    assert members["n"].sum() == c["n"].sum()
+    write_json(simple_graph, c, tmp_path / f"clus.json{ext}")
+    clusters = read_json(simple_graph, str(tmp_path / f"clus.json{ext}"))
+    assert clusters["n"].sum() == c["n"].sum()
+
+
+def test_log_level_gates_forwarded_records(caplog):
+    caplog.set_level(logging.DEBUG, logger="belinda")
+    set_log_level(logging.WARNING)
+    Graph("resources/discont_graph.txt")
+    assert not caplog.records
+    set_log_level(logging.DEBUG)
+    try:
+        Graph("resources/discont_graph.txt")
+    finally:
+        set_log_level(logging.WARNING)
+    assert any("finished in" in r.getMessage() for r in caplog.records)